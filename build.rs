@@ -0,0 +1,34 @@
+use std::{ env, fs, path::Path };
+
+/// Writes `scenarios.rs` to `OUT_DIR` with the scenarios of
+/// `fixtures/scenarios` embedded by `include_str!`, so that the
+/// binary bundles every scenario file without a hand-kept list.
+fn main()
+{
+  let dir = Path::new( env!( "CARGO_MANIFEST_DIR" ) ).join( "fixtures/scenarios" );
+  println!( "cargo:rerun-if-changed={}", dir.display() );
+
+  let mut paths : Vec< _ > = fs::read_dir( &dir )
+  .expect( "fixtures/scenarios is readable" )
+  .map( | entry | entry.expect( "fixtures/scenarios is readable" ).path() )
+  .filter( | path | path.extension().is_some_and( | x | x == "txt" ) )
+  .collect();
+  paths.sort();
+
+  let mut code = String::from
+  (
+    "/// Scenarios of `fixtures/scenarios` shown by `demo` without a\n\
+    /// directory, as `( name, text )`. They are embedded so that the\n\
+    /// binary does not depend on the source tree.\n",
+  );
+  code += &format!( "const SCENARIOS : [ ( &str, &str ); {} ] =\n[\n", paths.len() );
+  for path in &paths
+  {
+    let name = path.file_stem().unwrap().to_string_lossy();
+    code += &format!( "  ( {:?}, include_str!( {:?} ) ),\n", name, path.display().to_string() );
+  }
+  code += "];\n";
+
+  let out = Path::new( &env::var( "OUT_DIR" ).unwrap() ).join( "scenarios.rs" );
+  fs::write( out, code ).expect( "OUT_DIR is writable" );
+}
@@ -0,0 +1,129 @@
+use crate::json::{ number_to_json, Json };
+use crate::SelectionRequest;
+
+/// Counts of lines processed by [`run_batch`].
+#[ derive( Debug, PartialEq, Eq, Clone, Copy, Default ) ]
+pub struct BatchSummary
+{
+  /// Number of non-blank input lines.
+  pub total : usize,
+  /// Number of lines which could not be parsed or selected.
+  pub failed : usize,
+}
+
+/// Reads [`SelectionRequest`]s as JSON Lines from `input`, runs each
+/// of them and writes one JSON line per request to `output`. Blank
+/// lines are skipped.
+///
+/// Every output line holds the 1-based `line` number and either the
+/// echoed `request` with its `selected` values, the echoed `request`
+/// with an `error`, or the raw `input` text with an `error` if the
+/// line is not a valid request.
+///
+/// # Errors
+///
+/// Returns [`std::io::Error`] if reading `input` or writing `output`
+/// fails. Malformed lines are reported in `output` instead.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ run_batch, BatchSummary };
+/// let input = r#"{ "available" : [ 240, 720 ], "allowed" : [ "any" ], "preferred" : [ 1080 ] }
+/// not json
+/// "#;
+/// let mut output = vec![];
+/// let summary = run_batch::< i32 >( input.as_bytes(), &mut output ).unwrap();
+/// assert_eq!( summary, BatchSummary { total : 2, failed : 1 } );
+/// assert_eq!
+/// (
+///   String::from_utf8( output ).unwrap(),
+///   r#"{"line":1,"request":{"available":[240,720],"allowed":["any"],"preferred":[1080]},"selected":[720]}
+/// {"line":2,"input":"not json","error":"invalid JSON at offset 0"}
+/// "#,
+/// );
+/// ```
+pub fn run_batch< T >( input : impl std::io::BufRead, mut output : impl std::io::Write ) -> std::io::Result< BatchSummary >
+where
+  T : Ord + Clone + std::str::FromStr + std::fmt::Display,
+{
+  let mut summary = BatchSummary::default();
+
+  for ( index, line ) in input.lines().enumerate()
+  {
+    let line = line?;
+    if line.trim().is_empty()
+    {
+      continue;
+    }
+    summary.total += 1;
+
+    let mut fields = vec![ ( "line".to_string(), Json::Number( ( index + 1 ).to_string() ) ) ];
+    match SelectionRequest::< T >::from_json( &line )
+    {
+      Ok( request ) =>
+      {
+        fields.push( ( "request".to_string(), request.to_json_tree() ) );
+        match request.run()
+        {
+          Ok( response ) =>
+          {
+            fields.push( ( "selected".to_string(), Json::Array( response.selected.iter().map( number_to_json ).collect() ) ) );
+          }
+          Err( error ) =>
+          {
+            summary.failed += 1;
+            fields.push( ( "error".to_string(), Json::String( error.to_string() ) ) );
+          }
+        }
+      }
+      Err( error ) =>
+      {
+        summary.failed += 1;
+        fields.push( ( "input".to_string(), Json::String( line ) ) );
+        fields.push( ( "error".to_string(), Json::String( error.to_string() ) ) );
+      }
+    }
+
+    writeln!( output, "{}", Json::Object( fields ) )?;
+  }
+
+  Ok( summary )
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ run_batch, BatchSummary };
+
+  #[ test ]
+  fn test_selection_error()
+  {
+    let input = "\n{ \"available\" : [ 720, 240 ], \"allowed\" : [], \"preferred\" : [] }\n\n";
+    let mut output = vec![];
+    assert_eq!( run_batch::< i32 >( input.as_bytes(), &mut output ).unwrap(), BatchSummary { total : 1, failed : 1 } );
+    assert_eq!
+    (
+      String::from_utf8( output ).unwrap(),
+      concat!
+      (
+        r#"{"line":2,"request":{"available":[720,240],"allowed":[],"preferred":[]},"#,
+        r#""error":"`available` is not sorted or contains duplicates at index 1"}"#,
+        "\n",
+      ),
+    );
+  }
+
+  #[ test ]
+  fn test_missing_field()
+  {
+    let input = r#"{ "available" : [ 240 ] }"#;
+    let mut output = vec![];
+    assert_eq!( run_batch::< u64 >( input.as_bytes(), &mut output ).unwrap(), BatchSummary { total : 1, failed : 1 } );
+    assert_eq!
+    (
+      String::from_utf8( output ).unwrap(),
+      "{\"line\":1,\"input\":\"{ \\\"available\\\" : [ 240 ] }\",\"error\":\"missing field `$.allowed`\"}\n",
+    );
+  }
+}
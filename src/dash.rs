@@ -0,0 +1,540 @@
+use std::ops::Range;
+use crate::{ attempt_with, check_sorted_values, Argument, DashError, Rendition, SelectionError, SelectionOptions, Value };
+use crate::rendition::kbps;
+
+/// `Representation` element of an [`AdaptationSet`].
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Representation
+{
+  pub id : Option< String >,
+  /// Bitrate in bit/s, from `bandwidth`.
+  pub bandwidth : u64,
+  /// Video rendition from `width`, `height`, `frameRate` (rounded,
+  /// 0 if absent), `bandwidth` and `codecs`, each inherited from the
+  /// adaptation set when missing, or `None` without `height`.
+  pub rendition : Option< Rendition >,
+  /// Bytes of the element in the parsed text.
+  span : Range< usize >,
+}
+
+impl Representation
+{
+  /// Returns height of the rendition, if any.
+  pub fn height( &self ) -> Option< u32 >
+  {
+    self.rendition.as_ref().map( | x | x.height )
+  }
+}
+
+/// `AdaptationSet` element of an [`Mpd`].
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct AdaptationSet
+{
+  pub id : Option< String >,
+  pub representations : Vec< Representation >,
+  /// Bytes of the element in the parsed text.
+  span : Range< usize >,
+}
+
+impl AdaptationSet
+{
+  /// Returns sorted heights of the representations,
+  /// usable as `available`.
+  pub fn available( &self ) -> Vec< u32 >
+  {
+    let mut heights : Vec< _ > = self.representations.iter().filter_map( Representation::height ).collect();
+    heights.sort();
+    heights.dedup();
+    heights
+  }
+}
+
+/// DASH manifest reduced to its adaptation sets, which can be
+/// rewritten to a subset of their representations. Only the
+/// markup needed to find `AdaptationSet` and `Representation`
+/// elements is parsed, everything else is kept as is.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt, Mpd, Value::* };
+/// let mpd = Mpd::parse
+/// (
+///   r#"<MPD><Period>
+///   <AdaptationSet mimeType="video/mp4" frameRate="30">
+///     <Representation id="360" bandwidth="800000" width="640" height="360"/>
+///     <Representation id="720" bandwidth="2500000" width="1280" height="720"/>
+///   </AdaptationSet>
+/// </Period></MPD>"#,
+/// ).unwrap();
+///
+/// let available = mpd.adaptation_sets[ 0 ].available();
+/// assert_eq!( available, vec![ 360, 720 ] );
+///
+/// let selected = attempt( &available, &[ Any ], &[ Number( 1080 ) ] ).unwrap();
+/// assert_eq!
+/// (
+///   mpd.rewrite( &selected ),
+///   r#"<MPD><Period>
+///   <AdaptationSet mimeType="video/mp4" frameRate="30">
+///     <Representation id="720" bandwidth="2500000" width="1280" height="720"/>
+///   </AdaptationSet>
+/// </Period></MPD>"#,
+/// );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Mpd
+{
+  pub adaptation_sets : Vec< AdaptationSet >,
+  text : String,
+}
+
+impl Mpd
+{
+  /// Parses manifest from `text`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DashError`] if the markup is malformed, elements are
+  /// not nested properly or a representation has no valid `bandwidth`.
+  pub fn parse( text : &str ) -> Result< Self, DashError >
+  {
+    let mut adaptation_sets = vec![];
+    let mut set : Option< ( AdaptationSet, Vec< ( &str, &str ) > ) > = None;
+    let mut representation : Option< Representation > = None;
+
+    for tag in tags( text )?
+    {
+      let syntax = | message : &str | DashError::Syntax { offset : tag.span.start, message : message.to_string() };
+      match ( tag.name, tag.kind )
+      {
+        ( "AdaptationSet", Kind::Open | Kind::Empty ) =>
+        {
+          if set.is_some()
+          {
+            return Err( syntax( "nested `AdaptationSet`" ) );
+          }
+          let id = attribute( &tag.attributes, "id" ).map( String::from );
+          let adaptation_set = AdaptationSet { id, representations : vec![], span : tag.span.clone() };
+          if tag.kind == Kind::Empty
+          {
+            adaptation_sets.push( adaptation_set );
+          }
+          else
+          {
+            set = Some( ( adaptation_set, tag.attributes ) );
+          }
+        }
+        ( "AdaptationSet", Kind::Close ) =>
+        {
+          let ( mut adaptation_set, _ ) = set.take().ok_or_else( || syntax( "unexpected `</AdaptationSet>`" ) )?;
+          if representation.is_some()
+          {
+            return Err( syntax( "unclosed `Representation`" ) );
+          }
+          adaptation_set.span.end = tag.span.end;
+          adaptation_sets.push( adaptation_set );
+        }
+        ( "Representation", Kind::Open | Kind::Empty ) =>
+        {
+          let Some( ( adaptation_set, inherited ) ) = set.as_mut() else
+          {
+            return Err( syntax( "`Representation` outside of `AdaptationSet`" ) );
+          };
+          if representation.is_some()
+          {
+            return Err( syntax( "nested `Representation`" ) );
+          }
+          let parsed = self::representation( &tag, inherited )?;
+          if tag.kind == Kind::Empty
+          {
+            adaptation_set.representations.push( parsed );
+          }
+          else
+          {
+            representation = Some( parsed );
+          }
+        }
+        ( "Representation", Kind::Close ) =>
+        {
+          let mut parsed = representation.take().ok_or_else( || syntax( "unexpected `</Representation>`" ) )?;
+          parsed.span.end = tag.span.end;
+          if let Some( ( adaptation_set, _ ) ) = set.as_mut()
+          {
+            adaptation_set.representations.push( parsed );
+          }
+        }
+        _ => {}
+      }
+    }
+
+    if set.is_some()
+    {
+      return Err( DashError::Syntax { offset : text.len(), message : "unclosed `AdaptationSet`".to_string() } );
+    }
+    Ok( Self { adaptation_sets, text : text.to_string() } )
+  }
+
+  /// Returns sorted heights of every adaptation set.
+  pub fn available( &self ) -> Vec< u32 >
+  {
+    let mut heights : Vec< _ > = self.adaptation_sets.iter().flat_map( AdaptationSet::available ).collect();
+    heights.sort();
+    heights.dedup();
+    heights
+  }
+
+  /// Selects heights of each adaptation set on its own
+  /// with [`attempt_with`].
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if `allowed` or `preferred`
+  /// are not sorted or the selection fails.
+  pub fn selection
+  (
+    &self,
+    allowed : &[ Value< u32 > ],
+    preferred : &[ Value< u32 > ],
+    options : &SelectionOptions,
+  ) -> Result< Vec< Vec< u32 > >, SelectionError >
+  {
+    check_sorted_values( allowed, Argument::Allowed )?;
+    check_sorted_values( preferred, Argument::Preferred )?;
+    self.adaptation_sets.iter().map( | x | attempt_with( &x.available(), allowed, preferred, options ) ).collect()
+  }
+
+  /// Returns the manifest rewritten to the [`Mpd::selection`],
+  /// see [`Mpd::rewrite_sets`].
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if the selection fails.
+  pub fn select
+  (
+    &self,
+    allowed : &[ Value< u32 > ],
+    preferred : &[ Value< u32 > ],
+    options : &SelectionOptions,
+  ) -> Result< String, SelectionError >
+  {
+    Ok( self.rewrite_sets( &self.selection( allowed, preferred, options )? ) )
+  }
+
+  /// Returns the manifest keeping only representations with
+  /// one of the `selected` heights, see [`Mpd::rewrite_sets`].
+  pub fn rewrite( &self, selected : &[ u32 ] ) -> String
+  {
+    self.prune( | _, x | x.height().is_none_or( | x | selected.contains( &x ) ) )
+  }
+
+  /// Returns the manifest keeping only representations of each
+  /// adaptation set whose height is in the corresponding item of
+  /// `selection`. Representations without height are kept, and
+  /// adaptation sets left without representations are removed.
+  pub fn rewrite_sets( &self, selection : &[ Vec< u32 > ] ) -> String
+  {
+    self.prune( | index, x | x.height().is_none_or( | x | selection.get( index ).is_some_and( | s | s.contains( &x ) ) ) )
+  }
+
+  /// Removes representations for which `keep` returns `false`.
+  fn prune( &self, keep : impl Fn( usize, &Representation ) -> bool ) -> String
+  {
+    let mut removed = vec![];
+    for ( index, set ) in self.adaptation_sets.iter().enumerate()
+    {
+      let ( kept, dropped ) : ( Vec< _ >, Vec< _ > ) = set.representations.iter().partition( | x | keep( index, x ) );
+      if kept.is_empty() && !dropped.is_empty()
+      {
+        removed.push( lines( &self.text, &set.span ) );
+      }
+      else
+      {
+        removed.extend( dropped.iter().map( | x | lines( &self.text, &x.span ) ) );
+      }
+    }
+
+    let mut result = String::with_capacity( self.text.len() );
+    let mut offset = 0;
+    for span in removed
+    {
+      result.push_str( &self.text[ offset .. span.start ] );
+      offset = span.end;
+    }
+    result.push_str( &self.text[ offset .. ] );
+    result
+  }
+}
+
+/// Extends `span` to whole lines if it is the only markup on them.
+fn lines( text : &str, span : &Range< usize > ) -> Range< usize >
+{
+  let before = &text[ .. span.start ];
+  let after = &text[ span.end .. ];
+  let start = before.trim_end_matches( [ ' ', '\t' ] ).len();
+  let end = span.end + ( after.len() - after.trim_start_matches( [ ' ', '\t' ] ).len() );
+
+  let line_start = start == 0 || text[ .. start ].ends_with( '\n' );
+  match text[ end .. ].strip_prefix( "\r\n" ).map( | _ | 2 ).or( text[ end .. ].strip_prefix( '\n' ).map( | _ | 1 ) )
+  {
+    Some( newline ) if line_start => start .. end + newline,
+    None if line_start && end == text.len() => start .. end,
+    _ => span.clone(),
+  }
+}
+
+/// Creates representation from its tag, inheriting
+/// missing attributes from the adaptation set.
+fn representation( tag : &Tag< '_ >, inherited : &[ ( &str, &str ) ] ) -> Result< Representation, DashError >
+{
+  let offset = tag.span.start;
+  let own = | name : &str | attribute( &tag.attributes, name );
+  let attribute = | name : &str | own( name ).or_else( || attribute( inherited, name ) );
+  let invalid = | name : &str | DashError::Syntax { offset, message : format!( "invalid `{}`", name ) };
+  let number = | name : &str | -> Result< Option< u32 >, DashError >
+  {
+    attribute( name ).map( | x | x.parse().map_err( | _ | invalid( name ) ) ).transpose()
+  };
+
+  let bandwidth : u64 = own( "bandwidth" )
+  .ok_or( DashError::MissingAttribute { offset, name : "bandwidth" } )?
+  .parse()
+  .map_err( | _ | invalid( "bandwidth" ) )?;
+  let fps = match attribute( "frameRate" )
+  {
+    Some( rate ) =>
+    {
+      let ( numerator, denominator ) = rate.split_once( '/' ).unwrap_or( ( rate, "1" ) );
+      match ( numerator.parse::< f64 >(), denominator.parse::< f64 >() )
+      {
+        ( Ok( n ), Ok( d ) ) if d > 0.0 => ( n / d ).round() as u32,
+        _ => return Err( invalid( "frameRate" ) ),
+      }
+    }
+    None => 0,
+  };
+  let rendition = match number( "height" )?
+  {
+    Some( height ) => Some
+    (
+      Rendition
+      {
+        width : number( "width" )?.unwrap_or_default(),
+        height,
+        fps,
+        bitrate : kbps( bandwidth ).ok_or_else( || invalid( "bandwidth" ) )?,
+        codec : attribute( "codecs" ).unwrap_or_default().to_string(),
+      }
+    ),
+    None => None,
+  };
+
+  Ok
+  (
+    Representation
+    {
+      id : own( "id" ).map( String::from ),
+      bandwidth,
+      rendition,
+      span : tag.span.clone(),
+    }
+  )
+}
+
+/// Returns value of attribute `name`.
+fn attribute< 'a >( attributes : &[ ( &str, &'a str ) ], name : &str ) -> Option< &'a str >
+{
+  attributes.iter().find( | ( x, _ ) | *x == name ).map( | ( _, value ) | *value )
+}
+
+/// Kind of an XML tag.
+#[ derive( Debug, PartialEq, Eq, Clone, Copy ) ]
+enum Kind
+{
+  /// `<name>`
+  Open,
+  /// `</name>`
+  Close,
+  /// `<name/>`
+  Empty,
+}
+
+/// XML tag with its local name and raw attribute values.
+struct Tag< 'a >
+{
+  name : &'a str,
+  kind : Kind,
+  attributes : Vec< ( &'a str, &'a str ) >,
+  span : Range< usize >,
+}
+
+/// Markup skipped by [`tags`], as its start and end.
+const SKIPPED : [ ( &str, &str ); 4 ] = [ ( "<!--", "-->" ), ( "<![CDATA[", "]]>" ), ( "<?", "?>" ), ( "<!", ">" ) ];
+
+/// Splits `text` into tags, skipping comments, processing
+/// instructions, declarations and character data.
+fn tags( text : &str ) -> Result< Vec< Tag< '_ > >, DashError >
+{
+  let mut result = vec![];
+  let mut offset = 0;
+
+  while let Some( start ) = text[ offset .. ].find( '<' ).map( | x | x + offset )
+  {
+    let syntax = | message : &str | DashError::Syntax { offset : start, message : message.to_string() };
+    let rest = &text[ start .. ];
+    if let Some( ( _, end ) ) = SKIPPED.iter().find( | ( open, _ ) | rest.starts_with( open ) )
+    {
+      offset = start + rest.find( end ).ok_or_else( || syntax( "unterminated markup" ) )? + end.len();
+      continue;
+    }
+
+    let mut quote = None;
+    let length = rest
+    .char_indices()
+    .find( | &( _, c ) |
+    {
+      match quote
+      {
+        Some( q ) if q == c => quote = None,
+        None if c == '"' || c == '\'' => quote = Some( c ),
+        _ => {}
+      }
+      quote.is_none() && c == '>'
+    })
+    .ok_or_else( || syntax( "unterminated tag" ) )?
+    .0;
+
+    let body = &rest[ 1 .. length ];
+    let ( kind, body ) = match ( body.strip_prefix( '/' ), body.strip_suffix( '/' ) )
+    {
+      ( Some( body ), _ ) => ( Kind::Close, body ),
+      ( None, Some( body ) ) => ( Kind::Empty, body ),
+      ( None, None ) => ( Kind::Open, body ),
+    };
+    let ( name, mut attributes ) = body.split_once( char::is_whitespace ).unwrap_or( ( body, "" ) );
+    if name.is_empty()
+    {
+      return Err( syntax( "missing element name" ) );
+    }
+
+    let mut parsed = vec![];
+    loop
+    {
+      attributes = attributes.trim_start();
+      if attributes.is_empty()
+      {
+        break;
+      }
+      let ( key, value ) = attributes.split_once( '=' ).ok_or_else( || syntax( "expected `name=\"value\"`" ) )?;
+      let value = value.trim_start();
+      let quote = value.chars().next().filter( | x | *x == '"' || *x == '\'' ).ok_or_else( || syntax( "unquoted attribute value" ) )?;
+      let end = value[ 1 .. ].find( quote ).ok_or_else( || syntax( "unterminated attribute value" ) )? + 1;
+      parsed.push( ( key.trim(), &value[ 1 .. end ] ) );
+      attributes = &value[ end + 1 .. ];
+    }
+
+    let name = name.rsplit( ':' ).next().unwrap_or( name );
+    result.push( Tag { name, kind, attributes : parsed, span : start .. start + length + 1 } );
+    offset = start + length + 1;
+  }
+
+  Ok( result )
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::Mpd;
+  use crate::{ DashError, Rendition, SelectionOptions, Value::* };
+
+  const MPD : &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MPD xmlns="urn:mpeg:dash:schema:mpd:2011" type="static">
+  <!-- <Representation> in a comment is ignored -->
+  <Period id="1">
+    <AdaptationSet id="video" mimeType="video/mp4" codecs="avc1.64001f" frameRate="30000/1001">
+      <Representation id="v360" bandwidth="800000" width="640" height="360"/>
+      <Representation id="v720" bandwidth="2500000" width="1280" height="720">
+        <BaseURL>720.mp4</BaseURL>
+      </Representation>
+      <Representation id="v1080" bandwidth="5000000" width="1920" height="1080" frameRate="60" codecs="hvc1"/>
+    </AdaptationSet>
+    <AdaptationSet id="audio" mimeType="audio/mp4">
+      <Representation id="a128" bandwidth="128000"/>
+    </AdaptationSet>
+  </Period>
+</MPD>
+"#;
+
+  #[ test ]
+  fn test_parse()
+  {
+    let mpd = Mpd::parse( MPD ).unwrap();
+    assert_eq!( mpd.adaptation_sets.len(), 2 );
+    let video = &mpd.adaptation_sets[ 0 ];
+    assert_eq!( video.id.as_deref(), Some( "video" ) );
+    assert_eq!( video.representations[ 0 ].id.as_deref(), Some( "v360" ) );
+    assert_eq!
+    (
+      video.representations[ 0 ].rendition,
+      Some( Rendition { width : 640, height : 360, fps : 30, bitrate : 800, codec : "avc1.64001f".to_string() } ),
+    );
+    assert_eq!
+    (
+      video.representations[ 2 ].rendition,
+      Some( Rendition { width : 1920, height : 1080, fps : 60, bitrate : 5000, codec : "hvc1".to_string() } ),
+    );
+    assert_eq!( mpd.adaptation_sets[ 1 ].representations[ 0 ].rendition, None );
+    assert_eq!( mpd.available(), vec![ 360, 720, 1080 ] );
+  }
+
+  #[ test ]
+  fn test_select()
+  {
+    let mpd = Mpd::parse( MPD ).unwrap();
+    let rewritten = mpd.select( &[ AtLeast( 720 ) ], &[ Number( 720 ) ], &SelectionOptions::default() ).unwrap();
+    assert_eq!
+    (
+      rewritten,
+      MPD
+      .replace( "      <Representation id=\"v360\" bandwidth=\"800000\" width=\"640\" height=\"360\"/>\n", "" )
+      .replace( "      <Representation id=\"v1080\" bandwidth=\"5000000\" width=\"1920\" height=\"1080\" frameRate=\"60\" codecs=\"hvc1\"/>\n", "" ),
+    );
+    assert_eq!( Mpd::parse( &rewritten ).unwrap().available(), vec![ 720 ] );
+  }
+
+  #[ test ]
+  fn test_rewrite_removes_empty_sets()
+  {
+    let mpd = Mpd::parse( MPD ).unwrap();
+    assert_eq!( mpd.selection( &[ Number( 480 ) ], &[ Any ], &SelectionOptions::default() ), Ok( vec![ vec![], vec![] ] ) );
+
+    let rewritten = Mpd::parse( &mpd.rewrite( &[ 480 ] ) ).unwrap();
+    assert_eq!( rewritten.adaptation_sets.len(), 1 );
+    assert_eq!( rewritten.adaptation_sets[ 0 ].id.as_deref(), Some( "audio" ) );
+  }
+
+  #[ test ]
+  fn test_errors()
+  {
+    assert_eq!
+    (
+      Mpd::parse( "<MPD><Representation bandwidth=\"1\"/></MPD>" ),
+      Err( DashError::Syntax { offset : 5, message : "`Representation` outside of `AdaptationSet`".to_string() } ),
+    );
+    assert_eq!
+    (
+      Mpd::parse( "<MPD><AdaptationSet><Representation height=\"720\"/></AdaptationSet></MPD>" ),
+      Err( DashError::MissingAttribute { offset : 20, name : "bandwidth" } ),
+    );
+    assert_eq!
+    (
+      Mpd::parse( "<MPD><AdaptationSet height=720>" ),
+      Err( DashError::Syntax { offset : 5, message : "unquoted attribute value".to_string() } ),
+    );
+    assert_eq!
+    (
+      Mpd::parse( "<MPD><AdaptationSet>" ),
+      Err( DashError::Syntax { offset : 20, message : "unclosed `AdaptationSet`".to_string() } ),
+    );
+  }
+}
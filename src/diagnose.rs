@@ -0,0 +1,298 @@
+use crate::{ attempt_explained, attempt_with, Argument, Decision, Distance, MatchStrategy, NumberList };
+use crate::{ SelectionError, SelectionOptions, Tie, Value };
+
+/// Why a selection returned no values.
+#[ derive( Debug, PartialEq, Eq, Clone, Copy ) ]
+pub enum EmptyCause
+{
+  /// `available` is empty.
+  NoAvailable,
+  /// `preferred` is empty.
+  NoPreferred,
+  /// No available value is allowed.
+  NothingAllowed,
+  /// Every available value that is allowed is excluded with `!`.
+  AllExcluded,
+  /// No allowed value matches a preferred one exactly,
+  /// see [`MatchStrategy::ExactOnly`].
+  NoExactMatch,
+}
+
+/// Change of a single value in `allowed` or `preferred`.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum Change< T >
+{
+  Add( Value< T > ),
+  Remove( Value< T > ),
+}
+
+/// Change which makes the selection return `yields`.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Suggestion< T >
+{
+  pub argument : Argument,
+  pub change : Change< T >,
+  pub yields : Vec< T >,
+}
+
+/// Cause of an empty result with a suggestion how to fix it,
+/// if any single change does.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Diagnosis< T >
+{
+  pub cause : EmptyCause,
+  pub suggestion : Option< Suggestion< T > >,
+}
+
+/// Diagnoses why [`attempt_with`] returns no values for the given
+/// arguments. Returns `None` if the result is not empty.
+///
+/// The suggestion adds or removes a single value, choosing the one
+/// which `options.strategy` picks for the first preferred number.
+///
+/// # Errors
+///
+/// Returns [`SelectionError`] if [`attempt_with`] fails.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ diagnose_empty, SelectionOptions, Value::* };
+/// let diagnosis = diagnose_empty
+/// (
+///   &[ 240 ],
+///   &[ Number( 360 ), Number( 720 ) ],
+///   &[ Number( 1080 ) ],
+///   &SelectionOptions::default(),
+/// ).unwrap().unwrap();
+///
+/// assert_eq!
+/// (
+///   diagnosis.to_string(),
+///   "`allowed` shares nothing with `available`, adding 240 to `allowed` would yield [240]",
+/// );
+/// ```
+pub fn diagnose_empty< T : Ord + Clone + Distance >
+(
+  available : &[ T ],
+  allowed : &[ Value< T > ],
+  preferred : &[ Value< T > ],
+  options : &SelectionOptions,
+) -> Result< Option< Diagnosis< T > >, SelectionError >
+{
+  let explanation = attempt_explained( available, allowed, preferred, options )?;
+  if !explanation.selected.is_empty()
+  {
+    return Ok( None );
+  }
+
+  let values = | decision : fn( &Decision< T > ) -> bool | -> Vec< T >
+  {
+    explanation.available.iter().filter( | x | decision( &x.decision ) ).map( | x | x.value.clone() ).collect()
+  };
+  let closest = | candidates : &[ T ] | -> Option< T >
+  {
+    match preferred.iter().find_map( Value::as_number )
+    {
+      Some( preferred ) => options
+      .strategy
+      .pick( candidates, &preferred )
+      .or_else( || MatchStrategy::Nearest( Tie::Up ).pick( candidates, &preferred ) ),
+      None => candidates.first(),
+    }
+    .cloned()
+  };
+  let suggest = | argument : Argument, change : Option< Change< T > > | -> Result< Option< Suggestion< T > >, SelectionError >
+  {
+    let Some( change ) = change else
+    {
+      return Ok( None );
+    };
+    let mut allowed = allowed.to_vec();
+    let mut preferred = preferred.to_vec();
+    let values = match argument
+    {
+      Argument::Preferred => &mut preferred,
+      _ => &mut allowed,
+    };
+    match &change
+    {
+      Change::Add( value ) => insert( values, value.clone() ),
+      Change::Remove( value ) => values.retain( | x | x != value ),
+    }
+    let yields = attempt_with( available, &allowed, &preferred, options )?;
+    Ok( ( !yields.is_empty() ).then_some( Suggestion { argument, change, yields } ) )
+  };
+
+  let kept = values( | x | matches!( x, Decision::Kept( _ ) ) );
+  let not_allowed = values( | x | *x == Decision::NotAllowed );
+  let ( cause, suggestion ) = if available.is_empty()
+  {
+    ( EmptyCause::NoAvailable, None )
+  }
+  else if preferred.is_empty()
+  {
+    ( EmptyCause::NoPreferred, suggest( Argument::Preferred, Some( Change::Add( Value::Any ) ) )? )
+  }
+  else if kept.is_empty() && not_allowed.is_empty()
+  {
+    let excluded = values( | x | matches!( x, Decision::Excluded( _ ) ) );
+    let change = closest( &excluded ).map( | x | Change::Remove( Value::Not( x ) ) );
+    ( EmptyCause::AllExcluded, suggest( Argument::Allowed, change )? )
+  }
+  else if kept.is_empty()
+  {
+    let change = closest( &not_allowed ).map( | x | Change::Add( Value::Number( x ) ) );
+    ( EmptyCause::NothingAllowed, suggest( Argument::Allowed, change )? )
+  }
+  else
+  {
+    let change = closest( &kept ).map( | x | Change::Add( Value::Number( x ) ) );
+    ( EmptyCause::NoExactMatch, suggest( Argument::Preferred, change )? )
+  };
+
+  Ok( Some( Diagnosis { cause, suggestion } ) )
+}
+
+/// Inserts `value` keeping numbers of `values` sorted.
+fn insert< T : Ord >( values : &mut Vec< Value< T > >, value : Value< T > )
+{
+  let index = match &value
+  {
+    Value::Number( n ) => values.iter().position( | x | matches!( x, Value::Number( x ) if x > n ) ),
+    _ => None,
+  };
+  values.insert( index.unwrap_or( values.len() ), value );
+}
+
+impl std::fmt::Display for EmptyCause
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      EmptyCause::NoAvailable => write!( f, "`available` is empty" ),
+      EmptyCause::NoPreferred => write!( f, "`preferred` is empty" ),
+      EmptyCause::NothingAllowed => write!( f, "`allowed` shares nothing with `available`" ),
+      EmptyCause::AllExcluded => write!( f, "every allowed value is excluded" ),
+      EmptyCause::NoExactMatch => write!( f, "no allowed value equals a preferred one" ),
+    }
+  }
+}
+
+impl< T : std::fmt::Display + Clone > std::fmt::Display for Suggestion< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match &self.change
+    {
+      Change::Add( value ) => write!( f, "adding {} to `{}`", value, self.argument )?,
+      Change::Remove( value ) => write!( f, "removing {} from `{}`", value, self.argument )?,
+    }
+    write!( f, " would yield [{}]", NumberList( self.yields.clone() ) )
+  }
+}
+
+impl< T : std::fmt::Display + Clone > std::fmt::Display for Diagnosis< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    write!( f, "{}", self.cause )?;
+    match &self.suggestion
+    {
+      Some( suggestion ) => write!( f, ", {}", suggestion ),
+      None => Ok( () ),
+    }
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ diagnose_empty, Change, EmptyCause };
+  use crate::{ Argument, MatchStrategy, SelectionOptions, Value::* };
+
+  #[ test ]
+  fn test_not_empty()
+  {
+    assert_eq!( diagnose_empty( &[ 240 ], &[ Any ], &[ Any ], &SelectionOptions::default() ), Ok( None ) );
+  }
+
+  #[ test ]
+  fn test_nothing_allowed()
+  {
+    let diagnosis = diagnose_empty
+    (
+      &[ 720 ],
+      &[ Number( 240 ), Number( 360 ), Number( 1080 ) ],
+      &[ Number( 240 ), Number( 360 ) ],
+      &SelectionOptions::default(),
+    ).unwrap().unwrap();
+    assert_eq!( diagnosis.cause, EmptyCause::NothingAllowed );
+    let suggestion = diagnosis.suggestion.unwrap();
+    assert_eq!( suggestion.argument, Argument::Allowed );
+    assert_eq!( suggestion.change, Change::Add( Number( 720 ) ) );
+    assert_eq!( suggestion.yields, vec![ 720 ] );
+
+    let diagnosis = diagnose_empty
+    (
+      &[ 240, 360, 720 ],
+      &[ Number( 1080 ) ],
+      &[ Any, Number( 720 ) ],
+      &SelectionOptions::default(),
+    ).unwrap().unwrap();
+    assert_eq!
+    (
+      diagnosis.to_string(),
+      "`allowed` shares nothing with `available`, adding 720 to `allowed` would yield [720]",
+    );
+  }
+
+  #[ test ]
+  fn test_all_excluded()
+  {
+    let diagnosis = diagnose_empty
+    (
+      &[ 240, 360 ],
+      &[ Not( 240 ), Not( 360 ) ],
+      &[ Number( 300 ) ],
+      &SelectionOptions::default(),
+    ).unwrap().unwrap();
+    assert_eq!
+    (
+      diagnosis.to_string(),
+      "every allowed value is excluded, removing !360 from `allowed` would yield [360]",
+    );
+  }
+
+  #[ test ]
+  fn test_empty_arguments()
+  {
+    let diagnosis = diagnose_empty::< i32 >( &[], &[ Any ], &[ Any ], &SelectionOptions::default() ).unwrap().unwrap();
+    assert_eq!( diagnosis.cause, EmptyCause::NoAvailable );
+    assert_eq!( diagnosis.suggestion, None );
+
+    let diagnosis = diagnose_empty( &[ 240, 360 ], &[ Number( 360 ) ], &[], &SelectionOptions::default() ).unwrap().unwrap();
+    assert_eq!( diagnosis.to_string(), "`preferred` is empty, adding any to `preferred` would yield [360]" );
+
+    let diagnosis = diagnose_empty( &[ 240 ], &[ Number( 360 ) ], &[], &SelectionOptions::default() ).unwrap().unwrap();
+    assert_eq!( diagnosis.to_string(), "`preferred` is empty" );
+  }
+
+  #[ test ]
+  fn test_no_exact_match()
+  {
+    let diagnosis = diagnose_empty
+    (
+      &[ 240, 360, 720 ],
+      &[ Any ],
+      &[ Number( 300 ), Number( 1080 ) ],
+      &SelectionOptions { strategy : MatchStrategy::ExactOnly, ..Default::default() },
+    ).unwrap().unwrap();
+    assert_eq!
+    (
+      diagnosis.to_string(),
+      "no allowed value equals a preferred one, adding 360 to `preferred` would yield [360]",
+    );
+  }
+}
@@ -0,0 +1,312 @@
+/// Names an argument of [`attempt`](crate::attempt) and its stages.
+#[ derive( Debug, PartialEq, Eq, Clone, Copy ) ]
+pub enum Argument
+{
+  Available,
+  Allowed,
+  Preferred,
+}
+
+impl std::fmt::Display for Argument
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      Argument::Available => write!( f, "available" ),
+      Argument::Allowed => write!( f, "allowed" ),
+      Argument::Preferred => write!( f, "preferred" ),
+    }
+  }
+}
+
+/// Error returned by [`attempt`](crate::attempt) and its stages
+/// when the input cannot be processed.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum SelectionError
+{
+  /// [`Value::Any`](crate::Value::Any) reached a place where
+  /// only numbers are expected.
+  UnexpectedAny
+  {
+    argument : Argument,
+  },
+  /// Constraint such as [`Value::Range`](crate::Value::Range)
+  /// appeared outside of `allowed`.
+  UnexpectedConstraint
+  {
+    argument : Argument,
+  },
+  /// Value at `index` of `argument` is not strictly greater than
+  /// the previous one, i.e. it is out of order or duplicated.
+  Unsorted
+  {
+    argument : Argument,
+    index : usize,
+  },
+}
+
+impl std::fmt::Display for SelectionError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      SelectionError::UnexpectedAny { argument } => write!( f, "unexpected `any` in `{}`", argument ),
+      SelectionError::UnexpectedConstraint { argument } => write!( f, "unexpected constraint in `{}`", argument ),
+      SelectionError::Unsorted { argument, index } =>
+      {
+        write!( f, "`{}` is not sorted or contains duplicates at index {}", argument, index )
+      }
+    }
+  }
+}
+
+impl std::error::Error for SelectionError {}
+
+/// Error returned when parsing [`Value`](crate::Value)s from text.
+/// Offsets are byte offsets into the parsed string.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum ParseError
+{
+  /// `token` starting at `offset` is not a valid value.
+  InvalidValue
+  {
+    token : String,
+    offset : usize,
+  },
+  /// Value between two commas starting at `offset` is missing.
+  EmptyValue
+  {
+    offset : usize,
+  },
+  /// Bracket at `offset` has no matching counterpart.
+  UnbalancedBracket
+  {
+    offset : usize,
+  },
+}
+
+impl std::fmt::Display for ParseError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      ParseError::InvalidValue { token, offset } => write!( f, "invalid value `{}` at offset {}", token, offset ),
+      ParseError::EmptyValue { offset } => write!( f, "missing value at offset {}", offset ),
+      ParseError::UnbalancedBracket { offset } => write!( f, "unbalanced bracket at offset {}", offset ),
+    }
+  }
+}
+
+impl ParseError
+{
+  /// Moves the offset `by` bytes further, for errors in a
+  /// substring of the parsed string.
+  pub( crate ) fn shift( self, by : usize ) -> Self
+  {
+    match self
+    {
+      ParseError::InvalidValue { token, offset } => ParseError::InvalidValue { token, offset : offset + by },
+      ParseError::EmptyValue { offset } => ParseError::EmptyValue { offset : offset + by },
+      ParseError::UnbalancedBracket { offset } => ParseError::UnbalancedBracket { offset : offset + by },
+    }
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error returned when reading JSON. Paths are written
+/// like `$.allowed[1]`, where `$` is the whole document.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum JsonError
+{
+  /// Text is not valid JSON starting from byte `offset`.
+  Syntax
+  {
+    offset : usize,
+  },
+  /// Object at `path` has no required field.
+  MissingField
+  {
+    path : String,
+  },
+  /// Value at `path` has a wrong type or cannot be converted.
+  InvalidValue
+  {
+    path : String,
+  },
+}
+
+impl std::fmt::Display for JsonError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      JsonError::Syntax { offset } => write!( f, "invalid JSON at offset {}", offset ),
+      JsonError::MissingField { path } => write!( f, "missing field `{}`", path ),
+      JsonError::InvalidValue { path } => write!( f, "invalid value at `{}`", path ),
+    }
+  }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Error returned when loading [`Scenario`](crate::Scenario)s.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum ScenarioError
+{
+  /// File or directory at `path` cannot be read.
+  Io
+  {
+    path : String,
+    message : String,
+  },
+  /// Line `line` of scenario `name` is malformed.
+  Syntax
+  {
+    name : String,
+    line : usize,
+    message : String,
+  },
+  /// Scenario `name` has no `key` line.
+  MissingKey
+  {
+    name : String,
+    key : &'static str,
+  },
+}
+
+impl std::fmt::Display for ScenarioError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      ScenarioError::Io { path, message } => write!( f, "cannot read `{}`: {}", path, message ),
+      ScenarioError::Syntax { name, line, message } => write!( f, "scenario `{}` line {}: {}", name, line, message ),
+      ScenarioError::MissingKey { name, key } => write!( f, "scenario `{}` has no `{}`", name, key ),
+    }
+  }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// Error returned when registering a [`Ladder`](crate::Ladder).
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum LadderError
+{
+  /// Ladder `name` with `version` is already registered.
+  Duplicate
+  {
+    name : String,
+    version : u32,
+  },
+  /// Name is empty or contains `@`, which separates the version.
+  InvalidName
+  {
+    name : String,
+  },
+  /// Height at `index` of ladder `name` is not strictly greater
+  /// than the previous one.
+  Unsorted
+  {
+    name : String,
+    index : usize,
+  },
+}
+
+impl std::fmt::Display for LadderError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      LadderError::Duplicate { name, version } => write!( f, "ladder `{}@{}` is already registered", name, version ),
+      LadderError::InvalidName { name } => write!( f, "invalid ladder name `{}`", name ),
+      LadderError::Unsorted { name, index } =>
+      {
+        write!( f, "ladder `{}` is not sorted or contains duplicates at index {}", name, index )
+      }
+    }
+  }
+}
+
+impl std::error::Error for LadderError {}
+
+/// Error returned when parsing an HLS
+/// [`MasterPlaylist`](crate::MasterPlaylist). Lines are numbered from 1.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum HlsError
+{
+  /// Playlist does not start with `#EXTM3U`.
+  MissingHeader,
+  /// Attribute list of the tag at `line` is malformed.
+  Syntax
+  {
+    line : usize,
+    message : String,
+  },
+  /// Tag at `line` has no attribute `name`.
+  MissingAttribute
+  {
+    line : usize,
+    name : &'static str,
+  },
+  /// `#EXT-X-STREAM-INF` at `line` is not followed by a URI.
+  MissingUri
+  {
+    line : usize,
+  },
+}
+
+impl std::fmt::Display for HlsError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      HlsError::MissingHeader => write!( f, "playlist does not start with `#EXTM3U`" ),
+      HlsError::Syntax { line, message } => write!( f, "line {}: {}", line, message ),
+      HlsError::MissingAttribute { line, name } => write!( f, "line {}: missing attribute `{}`", line, name ),
+      HlsError::MissingUri { line } => write!( f, "line {}: `#EXT-X-STREAM-INF` is not followed by a URI", line ),
+    }
+  }
+}
+
+impl std::error::Error for HlsError {}
+
+/// Error returned when parsing a DASH [`Mpd`](crate::Mpd).
+/// Offsets are byte offsets into the parsed text.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum DashError
+{
+  /// Markup starting at `offset` is malformed.
+  Syntax
+  {
+    offset : usize,
+    message : String,
+  },
+  /// Element starting at `offset` has no attribute `name`.
+  MissingAttribute
+  {
+    offset : usize,
+    name : &'static str,
+  },
+}
+
+impl std::fmt::Display for DashError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      DashError::Syntax { offset, message } => write!( f, "{} at offset {}", message, offset ),
+      DashError::MissingAttribute { offset, name } => write!( f, "missing attribute `{}` at offset {}", name, offset ),
+    }
+  }
+}
+
+impl std::error::Error for DashError {}
@@ -0,0 +1,248 @@
+use crate::{ attempt_with, Distance, NumberList, SelectionError, SelectionOptions, Value };
+use crate::select::allowed_by;
+
+/// Why an available value was kept or dropped by `allowed`.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum Decision< T >
+{
+  /// Kept because it satisfies the given allowed value. An `allowed`
+  /// consisting only of exclusions keeps values as if it was `any`.
+  Kept( Value< T > ),
+  /// Dropped by the given exclusion.
+  Excluded( Value< T > ),
+  /// Dropped because no allowed value is satisfied.
+  NotAllowed,
+}
+
+/// How a preferred value was matched against allowed values.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum Outcome< T >
+{
+  /// Allowed value equal to the preferred one.
+  Exact( T ),
+  /// Closest allowed value greater than the preferred one.
+  RoundedUp( T ),
+  /// Closest allowed value smaller than the preferred one.
+  RoundedDown( T ),
+  /// Preferred value is `any`, so every allowed value is selected.
+  All,
+  /// Preferred value is not used since `any` is preferred as well.
+  Ignored,
+  /// No allowed value matches.
+  Unmatched,
+}
+
+/// Decision about a single available value.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct AvailableTrace< T >
+{
+  pub value : T,
+  pub decision : Decision< T >,
+}
+
+/// Outcome of a single preferred value.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct PreferredTrace< T >
+{
+  pub preferred : Value< T >,
+  pub outcome : Outcome< T >,
+}
+
+/// Trace of [`attempt_with`] returned by [`attempt_explained`].
+/// Its `Display` renders the trace for humans.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Explanation< T >
+{
+  pub available : Vec< AvailableTrace< T > >,
+  pub preferred : Vec< PreferredTrace< T > >,
+  pub selected : Vec< T >,
+}
+
+/// Same as [`attempt_with`] but also explains why each available
+/// value was kept or dropped and how each preferred value was matched.
+///
+/// # Errors
+///
+/// Returns [`SelectionError`] if [`attempt_with`] fails.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt_explained, Decision, Outcome, SelectionOptions, Value::* };
+/// let explanation = attempt_explained
+/// (
+///   &[ 240, 360, 720 ],
+///   &[ Number( 360 ), Number( 720 ) ],
+///   &[ Number( 1080 ) ],
+///   &SelectionOptions::default(),
+/// ).unwrap();
+///
+/// assert_eq!( explanation.available[ 0 ].decision, Decision::NotAllowed );
+/// assert_eq!( explanation.available[ 2 ].decision, Decision::Kept( Number( 720 ) ) );
+/// assert_eq!( explanation.preferred[ 0 ].outcome, Outcome::RoundedDown( 720 ) );
+/// assert_eq!( explanation.selected, vec![ 720 ] );
+/// ```
+pub fn attempt_explained< T : Ord + Clone + Distance >
+(
+  available : &[ T ],
+  allowed : &[ Value< T > ],
+  preferred : &[ Value< T > ],
+  options : &SelectionOptions,
+) -> Result< Explanation< T >, SelectionError >
+{
+  let selected = attempt_with( available, allowed, preferred, options )?;
+
+  let decide = | value : &T | match allowed_by( allowed, value )
+  {
+    Ok( by ) => Decision::Kept( by.cloned().unwrap_or( Value::Any ) ),
+    Err( Some( by ) ) => Decision::Excluded( by.clone() ),
+    Err( None ) => Decision::NotAllowed,
+  };
+  let available : Vec< _ > = available
+  .iter()
+  .map( | value | AvailableTrace { value : value.clone(), decision : decide( value ) } )
+  .collect();
+
+  let kept : Vec< _ > = available
+  .iter()
+  .filter( | x | matches!( x.decision, Decision::Kept( _ ) ) )
+  .map( | x | x.value.clone() )
+  .collect();
+  let any = preferred.contains( &Value::Any );
+  let preferred = preferred
+  .iter()
+  .map( | value |
+  {
+    let outcome = match value
+    {
+      Value::Any => Outcome::All,
+      _ if any => Outcome::Ignored,
+      Value::Number( n ) => match options.strategy.pick( &kept, n )
+      {
+        Some( x ) if x == n => Outcome::Exact( x.clone() ),
+        Some( x ) if x > n => Outcome::RoundedUp( x.clone() ),
+        Some( x ) => Outcome::RoundedDown( x.clone() ),
+        None => Outcome::Unmatched,
+      },
+      _ => Outcome::Unmatched,
+    };
+    PreferredTrace { preferred : value.clone(), outcome }
+  })
+  .collect();
+
+  Ok( Explanation { available, preferred, selected } )
+}
+
+impl< T : std::fmt::Display + Clone > std::fmt::Display for Explanation< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    writeln!( f, "available :" )?;
+    for trace in &self.available
+    {
+      match &trace.decision
+      {
+        Decision::Kept( by ) => writeln!( f, "  {} kept, allowed by `{}`", trace.value, by )?,
+        Decision::Excluded( by ) => writeln!( f, "  {} dropped, excluded by `{}`", trace.value, by )?,
+        Decision::NotAllowed => writeln!( f, "  {} dropped, not allowed", trace.value )?,
+      }
+    }
+    writeln!( f, "preferred :" )?;
+    for trace in &self.preferred
+    {
+      match &trace.outcome
+      {
+        Outcome::Exact( x ) => writeln!( f, "  {} matched exactly by {}", trace.preferred, x )?,
+        Outcome::RoundedUp( x ) => writeln!( f, "  {} rounded up to {}", trace.preferred, x )?,
+        Outcome::RoundedDown( x ) => writeln!( f, "  {} rounded down to {}", trace.preferred, x )?,
+        Outcome::All => writeln!( f, "  {} selects every allowed value", trace.preferred )?,
+        Outcome::Ignored => writeln!( f, "  {} ignored, `any` is preferred", trace.preferred )?,
+        Outcome::Unmatched => writeln!( f, "  {} not matched", trace.preferred )?,
+      }
+    }
+    writeln!( f, "returns   : {}", NumberList( self.selected.clone() ) )
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ attempt_explained, Decision, Outcome };
+  use crate::{ MatchStrategy, SelectionOptions, Value, Value::* };
+
+  #[ test ]
+  fn test_decisions()
+  {
+    let explanation = attempt_explained
+    (
+      &[ 240, 360, 480, 720 ],
+      &[ Value::range( 300 .. ), Not( 480 ), Number( 720 ) ],
+      &[ Any, Number( 720 ) ],
+      &SelectionOptions::default(),
+    ).unwrap();
+
+    assert_eq!
+    (
+      explanation.available.iter().map( | x | x.decision.clone() ).collect::< Vec< _ > >(),
+      vec!
+      [
+        Decision::NotAllowed,
+        Decision::Kept( Value::range( 300 .. ) ),
+        Decision::Excluded( Not( 480 ) ),
+        Decision::Kept( Value::range( 300 .. ) ),
+      ],
+    );
+    assert_eq!
+    (
+      explanation.preferred.iter().map( | x | x.outcome.clone() ).collect::< Vec< _ > >(),
+      vec![ Outcome::All, Outcome::Ignored ],
+    );
+    assert_eq!( explanation.selected, vec![ 360, 720 ] );
+  }
+
+  #[ test ]
+  fn test_outcomes()
+  {
+    let explanation = attempt_explained
+    (
+      &[ 240, 360, 720 ],
+      &[ Not( 240 ) ],
+      &[ Number( 100 ), Number( 360 ), Number( 1080 ), Number( 500 ) ],
+      &SelectionOptions { strategy : MatchStrategy::ExactOnly, ..Default::default() },
+    ).unwrap();
+
+    assert_eq!( explanation.available[ 1 ].decision, Decision::Kept( Any ) );
+    assert_eq!
+    (
+      explanation.preferred.iter().map( | x | x.outcome.clone() ).collect::< Vec< _ > >(),
+      vec![ Outcome::Unmatched, Outcome::Exact( 360 ), Outcome::Unmatched, Outcome::Unmatched ],
+    );
+  }
+
+  #[ test ]
+  fn test_display()
+  {
+    let explanation = attempt_explained
+    (
+      &[ 240, 360, 720 ],
+      &[ Number( 360 ), Number( 720 ) ],
+      &[ Number( 300 ), Number( 1080 ) ],
+      &SelectionOptions::default(),
+    ).unwrap();
+
+    assert_eq!
+    (
+      explanation.to_string(),
+      "\
+available :
+  240 dropped, not allowed
+  360 kept, allowed by `360`
+  720 kept, allowed by `720`
+preferred :
+  300 rounded up to 360
+  1080 rounded down to 720
+returns   : 360, 720
+",
+    );
+  }
+}
@@ -0,0 +1,319 @@
+use crate::{ attempt_with, check_sorted_values, Argument, Distance, ParseError, SelectionError, SelectionOptions, Value, ValueList };
+
+/// Relaxed selection tried by [`FallbackPolicy`].
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub enum Fallback< T = i32 >
+{
+  /// Selects with the given **sorted** values in place of `allowed`,
+  /// keeping only its exclusions like [`Fallback::Any`].
+  Allowed( Vec< Value< T > > ),
+  /// Selects with `allowed` replaced by `any`, keeping only its
+  /// exclusions since they usually mark values which must never
+  /// be selected.
+  Any,
+  /// Selects the lowest available value not excluded in `allowed`,
+  /// ignoring every other argument.
+  Lowest,
+}
+
+/// Chain of [`Fallback`]s tried in order when a selection
+/// returns no values.
+///
+/// Parses from fallbacks separated by `;`, each being `any`,
+/// `lowest` or a list of allowed values:
+///
+/// ```
+/// # use task_rust::{ Fallback, FallbackPolicy, Value };
+/// let policy : FallbackPolicy = "360..=1080; any; lowest".parse().unwrap();
+/// assert_eq!
+/// (
+///   policy.chain,
+///   vec![ Fallback::Allowed( vec![ Value::range( 360 ..= 1080 ) ] ), Fallback::Any, Fallback::Lowest ],
+/// );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct FallbackPolicy< T = i32 >
+{
+  pub chain : Vec< Fallback< T > >,
+}
+
+/// Result of [`FallbackPolicy::attempt`].
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct FallbackSelection< T = i32 >
+{
+  pub selected : Vec< T >,
+  /// Index in the chain of the fallback which produced `selected`,
+  /// `None` if the strict selection did.
+  pub level : Option< usize >,
+}
+
+/// Tries `any` and then the lowest available value.
+impl< T > Default for FallbackPolicy< T >
+{
+  fn default() -> Self
+  {
+    Self { chain : vec![ Fallback::Any, Fallback::Lowest ] }
+  }
+}
+
+impl< T : Ord + Clone + Distance > FallbackPolicy< T >
+{
+  /// Runs [`attempt_with`] and, if it returns no values, tries each
+  /// fallback of the chain until one returns some. The result stays
+  /// empty only if every fallback fails too.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError::Unsorted`] with [`Argument::Allowed`]
+  /// if the values of a [`Fallback::Allowed`] are not sorted, or
+  /// [`SelectionError`] if [`attempt_with`] fails.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use task_rust::{ FallbackPolicy, FallbackSelection, SelectionOptions, Value::* };
+  /// let policy : FallbackPolicy = "720..; any".parse().unwrap();
+  /// assert_eq!
+  /// (
+  ///   policy.attempt
+  ///   (
+  ///     &[ 240, 360, 720 ],
+  ///     &[ Number( 1080 ) ],
+  ///     &[ Number( 1080 ) ],
+  ///     &SelectionOptions::default(),
+  ///   ),
+  ///   Ok( FallbackSelection { selected : vec![ 720 ], level : Some( 0 ) } ),
+  /// );
+  /// ```
+  pub fn attempt
+  (
+    &self,
+    available : &[ T ],
+    allowed : &[ Value< T > ],
+    preferred : &[ Value< T > ],
+    options : &SelectionOptions,
+  ) -> Result< FallbackSelection< T >, SelectionError >
+  {
+    for fallback in &self.chain
+    {
+      if let Fallback::Allowed( allowed ) = fallback
+      {
+        check_sorted_values( allowed, Argument::Allowed )?;
+      }
+    }
+
+    let selected = attempt_with( available, allowed, preferred, options )?;
+    if !selected.is_empty()
+    {
+      return Ok( FallbackSelection { selected, level : None } );
+    }
+
+    let exclusions : Vec< _ > = allowed.iter().filter( | x | matches!( x, Value::Not( _ ) ) ).cloned().collect();
+    for ( level, fallback ) in self.chain.iter().enumerate()
+    {
+      let selected = match fallback
+      {
+        Fallback::Allowed( allowed ) =>
+        {
+          let mut allowed = allowed.clone();
+          allowed.extend( exclusions.iter().cloned() );
+          attempt_with( available, &allowed, preferred, options )?
+        }
+        Fallback::Any =>
+        {
+          let mut allowed = exclusions.clone();
+          allowed.push( Value::Any );
+          attempt_with( available, &allowed, preferred, options )?
+        }
+        Fallback::Lowest => available
+        .iter()
+        .filter( | x | exclusions.iter().all( | e | e.matches( x ) ) )
+        .min()
+        .cloned()
+        .into_iter()
+        .collect(),
+      };
+      if !selected.is_empty()
+      {
+        return Ok( FallbackSelection { selected, level : Some( level ) } );
+      }
+    }
+
+    Ok( FallbackSelection { selected : vec![], level : None } )
+  }
+}
+
+impl< T : std::str::FromStr > std::str::FromStr for Fallback< T >
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    match s.trim()
+    {
+      "any" => Ok( Fallback::Any ),
+      "lowest" => Ok( Fallback::Lowest ),
+      _ => s.parse().map( | list : ValueList< T > | Fallback::Allowed( list.0 ) ),
+    }
+  }
+}
+
+impl< T : std::str::FromStr > std::str::FromStr for FallbackPolicy< T >
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    let mut offset = 0;
+    let mut chain = vec![];
+    for fallback in s.split( ';' )
+    {
+      chain.push( fallback.parse().map_err( | error : ParseError | error.shift( offset ) )? );
+      offset += fallback.len() + 1;
+    }
+    Ok( Self { chain } )
+  }
+}
+
+impl< T : std::fmt::Display > std::fmt::Display for Fallback< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      Fallback::Allowed( allowed ) =>
+      {
+        for ( index, value ) in allowed.iter().enumerate()
+        {
+          if index > 0
+          {
+            write!( f, ", " )?;
+          }
+          write!( f, "{}", value )?;
+        }
+        Ok( () )
+      }
+      Fallback::Any => write!( f, "any" ),
+      Fallback::Lowest => write!( f, "lowest" ),
+    }
+  }
+}
+
+impl< T : std::fmt::Display > std::fmt::Display for FallbackPolicy< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    for ( index, fallback ) in self.chain.iter().enumerate()
+    {
+      if index > 0
+      {
+        write!( f, "; " )?;
+      }
+      write!( f, "{}", fallback )?;
+    }
+    Ok( () )
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ Fallback, FallbackPolicy, FallbackSelection };
+  use crate::{ Argument, MatchStrategy, ParseError, SelectionError, SelectionOptions, Value, Value::* };
+
+  #[ test ]
+  fn test_strict()
+  {
+    assert_eq!
+    (
+      FallbackPolicy::default().attempt( &[ 240, 720 ], &[ Number( 720 ) ], &[ Any ], &SelectionOptions::default() ),
+      Ok( FallbackSelection { selected : vec![ 720 ], level : None } ),
+    );
+  }
+
+  #[ test ]
+  fn test_levels()
+  {
+    let policy = FallbackPolicy
+    {
+      chain : vec![ Fallback::Allowed( vec![ Value::range( 1080 .. ) ] ), Fallback::Any, Fallback::Lowest ],
+    };
+    let options = SelectionOptions { strategy : MatchStrategy::ExactOnly, ..Default::default() };
+
+    assert_eq!
+    (
+      policy.attempt( &[ 240, 360, 720 ], &[ Number( 480 ), Not( 720 ) ], &[ Number( 720 ) ], &options ),
+      Ok( FallbackSelection { selected : vec![ 240 ], level : Some( 2 ) } ),
+    );
+    assert_eq!
+    (
+      policy.attempt( &[ 240, 360, 720 ], &[ Number( 480 ) ], &[ Number( 720 ) ], &options ),
+      Ok( FallbackSelection { selected : vec![ 720 ], level : Some( 1 ) } ),
+    );
+    assert_eq!
+    (
+      policy.attempt( &[ 240, 1080 ], &[ Number( 480 ) ], &[ Number( 1080 ) ], &options ),
+      Ok( FallbackSelection { selected : vec![ 1080 ], level : Some( 0 ) } ),
+    );
+  }
+
+  #[ test ]
+  fn test_exclusions()
+  {
+    let policy = FallbackPolicy { chain : vec![ Fallback::Lowest ] };
+    assert_eq!
+    (
+      policy.attempt( &[ 240, 360, 720 ], &[ Number( 1080 ), Not( 240 ) ], &[ Number( 1080 ) ], &SelectionOptions::default() ),
+      Ok( FallbackSelection { selected : vec![ 360 ], level : Some( 0 ) } ),
+    );
+    assert_eq!
+    (
+      policy.attempt( &[ 240 ], &[ Not( 240 ) ], &[ Any ], &SelectionOptions::default() ),
+      Ok( FallbackSelection { selected : vec![], level : None } ),
+    );
+  }
+
+  #[ test ]
+  fn test_allowed_keeps_exclusions()
+  {
+    let policy : FallbackPolicy = "360..=720; any".parse().unwrap();
+    assert_eq!
+    (
+      policy.attempt( &[ 240, 480, 720 ], &[ Number( 1080 ), Not( 480 ) ], &[ Number( 480 ) ], &SelectionOptions::default() ),
+      Ok( FallbackSelection { selected : vec![ 720 ], level : Some( 0 ) } ),
+    );
+  }
+
+  #[ test ]
+  fn test_unsorted()
+  {
+    let policy : FallbackPolicy = "720, 360".parse().unwrap();
+    assert_eq!
+    (
+      policy.attempt( &[ 240, 360, 720 ], &[ Number( 1080 ) ], &[ Any ], &SelectionOptions::default() ),
+      Err( SelectionError::Unsorted { argument : Argument::Allowed, index : 1 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_exhausted()
+  {
+    assert_eq!
+    (
+      FallbackPolicy::< i32 >::default().attempt( &[], &[ Any ], &[ Any ], &SelectionOptions::default() ),
+      Ok( FallbackSelection { selected : vec![], level : None } ),
+    );
+  }
+
+  #[ test ]
+  fn test_parse()
+  {
+    let policy : FallbackPolicy = "[ 360, 720 ];any ; lowest".parse().unwrap();
+    assert_eq!( policy.to_string(), "360, 720; any; lowest" );
+    assert_eq!
+    (
+      "any; 360, x".parse::< FallbackPolicy >(),
+      Err( ParseError::InvalidValue { token : "x".to_string(), offset : 10 } ),
+    );
+  }
+}
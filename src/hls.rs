@@ -0,0 +1,452 @@
+use crate::{ attempt_with, check_sorted_values, Argument, HlsError, Rendition, SelectionError, SelectionOptions, Value };
+use crate::rendition::kbps;
+
+/// Variant stream of a [`MasterPlaylist`], declared by
+/// `#EXT-X-STREAM-INF` and the URI following it.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Variant
+{
+  pub uri : String,
+  /// Peak bitrate in bit/s, from `BANDWIDTH`.
+  pub bandwidth : u64,
+  /// Video rendition from `RESOLUTION`, `FRAME-RATE` (rounded, 0 if
+  /// absent), `BANDWIDTH` and `CODECS`, or `None` for variants
+  /// without `RESOLUTION`, e.g. audio only ones.
+  pub rendition : Option< Rendition >,
+  /// Every attribute of the tag, with quoted values unquoted.
+  pub attributes : Vec< ( String, String ) >,
+  /// Line of the tag, counted from 1.
+  pub line : usize,
+}
+
+impl Variant
+{
+  /// Returns value of attribute `name`.
+  pub fn attribute( &self, name : &str ) -> Option< &str >
+  {
+    self.attributes.iter().find( | ( x, _ ) | x == name ).map( | ( _, value ) | value.as_str() )
+  }
+
+  /// Returns height of the rendition, if any.
+  pub fn height( &self ) -> Option< u32 >
+  {
+    self.rendition.as_ref().map( | x | x.height )
+  }
+}
+
+/// HLS master playlist with its variant streams, which can be
+/// rewritten to a subset of them.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt, MasterPlaylist, Value::* };
+/// let playlist = MasterPlaylist::parse
+/// (
+///   "#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360
+/// low.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=2500000,RESOLUTION=1280x720
+/// mid.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=5000000,RESOLUTION=1920x1080
+/// high.m3u8
+/// ",
+/// ).unwrap();
+///
+/// let available = playlist.available();
+/// assert_eq!( available, vec![ 360, 720, 1080 ] );
+///
+/// let selected = attempt( &available, &[ Any ], &[ Number( 700 ) ] ).unwrap();
+/// assert_eq!( playlist.uris( &selected ), vec![ "mid.m3u8" ] );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct MasterPlaylist
+{
+  pub variants : Vec< Variant >,
+  /// Lines of the original text, kept for rewriting.
+  lines : Vec< String >,
+}
+
+/// Attributes of `#EXT-X-STREAM-INF` naming a group of
+/// `#EXT-X-MEDIA` tags with the same `TYPE`.
+const GROUPS : [ &str; 4 ] = [ "AUDIO", "VIDEO", "SUBTITLES", "CLOSED-CAPTIONS" ];
+
+impl MasterPlaylist
+{
+  /// Parses master playlist from `text`, ignoring every tag except
+  /// `#EXT-X-STREAM-INF`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`HlsError`] if the header, an attribute or a URI is
+  /// missing or an attribute list is malformed.
+  pub fn parse( text : &str ) -> Result< Self, HlsError >
+  {
+    let mut lines = text.lines().map( str::trim ).enumerate().filter( | ( _, x ) | !x.is_empty() );
+    if lines.next().map( | ( _, x ) | x ) != Some( "#EXTM3U" )
+    {
+      return Err( HlsError::MissingHeader );
+    }
+
+    let mut variants = vec![];
+    while let Some( ( index, line ) ) = lines.next()
+    {
+      let Some( attributes ) = line.strip_prefix( "#EXT-X-STREAM-INF:" ) else
+      {
+        continue;
+      };
+      let line = index + 1;
+      let attributes = parse_attributes( attributes, line )?;
+      let uri = lines
+      .find( | ( _, x ) | !x.starts_with( '#' ) || x.starts_with( "#EXT" ) )
+      .filter( | ( _, x ) | !x.starts_with( '#' ) )
+      .ok_or( HlsError::MissingUri { line } )?
+      .1;
+      variants.push( variant( uri, attributes, line )? );
+    }
+
+    Ok( Self { variants, lines : text.lines().map( String::from ).collect() } )
+  }
+
+  /// Returns sorted heights of the variants, usable as `available`.
+  pub fn available( &self ) -> Vec< u32 >
+  {
+    let mut heights : Vec< _ > = self.variants.iter().filter_map( Variant::height ).collect();
+    heights.sort();
+    heights.dedup();
+    heights
+  }
+
+  /// Returns variants with one of the `selected` heights,
+  /// in playlist order.
+  pub fn variants_for( &self, selected : &[ u32 ] ) -> Vec< &Variant >
+  {
+    self.variants.iter().filter( | x | x.height().is_some_and( | x | selected.contains( &x ) ) ).collect()
+  }
+
+  /// Returns URIs of [`MasterPlaylist::variants_for`].
+  pub fn uris( &self, selected : &[ u32 ] ) -> Vec< &str >
+  {
+    self.variants_for( selected ).into_iter().map( | x | x.uri.as_str() ).collect()
+  }
+
+  /// Returns the playlist text keeping only variants of
+  /// [`MasterPlaylist::variants_for`] and variants without height, e.g.
+  /// audio only ones. Tags `#EXT-X-MEDIA` of groups no kept variant
+  /// refers to and `#EXT-X-I-FRAME-STREAM-INF` of other heights are
+  /// removed too, every other line is kept as is.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use task_rust::MasterPlaylist;
+  /// let playlist = MasterPlaylist::parse
+  /// (
+  ///   "#EXTM3U
+  /// #EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360
+  /// low.m3u8
+  /// #EXT-X-STREAM-INF:BANDWIDTH=2500000,RESOLUTION=1280x720
+  /// mid.m3u8
+  /// ",
+  /// ).unwrap();
+  ///
+  /// assert_eq!
+  /// (
+  ///   playlist.rewrite( &[ 720 ] ),
+  ///   "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=2500000,RESOLUTION=1280x720\nmid.m3u8\n",
+  /// );
+  /// ```
+  pub fn rewrite( &self, selected : &[ u32 ] ) -> String
+  {
+    let kept : Vec< _ > = self.variants.iter().filter( | x | x.height().is_none_or( | x | selected.contains( &x ) ) ).collect();
+    let mut removed = vec![ false; self.lines.len() ];
+
+    for variant in self.variants.iter().filter( | x | !kept.contains( x ) )
+    {
+      let uri = ( variant.line .. self.lines.len() )
+      .find( | x | self.lines[ *x ].trim() == variant.uri )
+      .unwrap_or( variant.line - 1 );
+      removed[ variant.line - 1 ..= uri ].fill( true );
+    }
+
+    for ( index, line ) in self.lines.iter().enumerate()
+    {
+      let line = line.trim();
+      if let Some( attributes ) = line.strip_prefix( "#EXT-X-MEDIA:" )
+      {
+        let attributes = parse_attributes( attributes, index + 1 ).unwrap_or_default();
+        let attribute = | name : &str | attributes.iter().find( | ( x, _ ) | x == name ).map( | ( _, value ) | value.as_str() );
+        if let ( Some( kind ), Some( group ) ) = ( attribute( "TYPE" ), attribute( "GROUP-ID" ) )
+        {
+          removed[ index ] = GROUPS.contains( &kind ) && !kept.iter().any( | x | x.attribute( kind ) == Some( group ) );
+        }
+      }
+      else if let Some( attributes ) = line.strip_prefix( "#EXT-X-I-FRAME-STREAM-INF:" )
+      {
+        let attributes = parse_attributes( attributes, index + 1 ).unwrap_or_default();
+        let height = attributes
+        .iter()
+        .find( | ( x, _ ) | x == "RESOLUTION" )
+        .and_then( | ( _, x ) | x.split_once( [ 'x', 'X' ] )?.1.parse::< u32 >().ok() );
+        removed[ index ] = height.is_some_and( | x | !selected.contains( &x ) );
+      }
+    }
+
+    self
+    .lines
+    .iter()
+    .zip( removed )
+    .filter( | ( _, removed ) | !removed )
+    .map( | ( line, _ ) | format!( "{}\n", line ) )
+    .collect()
+  }
+
+  /// Selects heights of the variants with [`attempt_with`] and
+  /// returns the playlist rewritten to them, see
+  /// [`MasterPlaylist::rewrite`]. The result has no variants if
+  /// nothing is selected.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if `allowed` or `preferred`
+  /// are not sorted or the selection fails.
+  pub fn select
+  (
+    &self,
+    allowed : &[ Value< u32 > ],
+    preferred : &[ Value< u32 > ],
+    options : &SelectionOptions,
+  ) -> Result< String, SelectionError >
+  {
+    check_sorted_values( allowed, Argument::Allowed )?;
+    check_sorted_values( preferred, Argument::Preferred )?;
+    let selected = attempt_with( &self.available(), allowed, preferred, options )?;
+    Ok( self.rewrite( &selected ) )
+  }
+}
+
+/// Creates variant from the attributes of its tag at `line`.
+fn variant( uri : &str, attributes : Vec< ( String, String ) >, line : usize ) -> Result< Variant, HlsError >
+{
+  let attribute = | name : &str | attributes.iter().find( | ( x, _ ) | x == name ).map( | ( _, value ) | value.as_str() );
+  let invalid = | name : &str | HlsError::Syntax { line, message : format!( "invalid `{}`", name ) };
+
+  let bandwidth : u64 = attribute( "BANDWIDTH" )
+  .ok_or( HlsError::MissingAttribute { line, name : "BANDWIDTH" } )?
+  .parse()
+  .map_err( | _ | invalid( "BANDWIDTH" ) )?;
+  let fps = match attribute( "FRAME-RATE" )
+  {
+    Some( fps ) => fps.parse::< f64 >().map_err( | _ | invalid( "FRAME-RATE" ) )?.round() as u32,
+    None => 0,
+  };
+  let rendition = match attribute( "RESOLUTION" )
+  {
+    Some( resolution ) =>
+    {
+      let ( width, height ) = resolution
+      .split_once( [ 'x', 'X' ] )
+      .and_then( | ( w, h ) | Some( ( w.parse().ok()?, h.parse().ok()? ) ) )
+      .ok_or_else( || invalid( "RESOLUTION" ) )?;
+      Some
+      (
+        Rendition
+        {
+          width,
+          height,
+          fps,
+          bitrate : kbps( bandwidth ).ok_or_else( || invalid( "BANDWIDTH" ) )?,
+          codec : attribute( "CODECS" ).unwrap_or_default().to_string(),
+        }
+      )
+    }
+    None => None,
+  };
+
+  Ok( Variant { uri : uri.to_string(), bandwidth, rendition, attributes, line } )
+}
+
+/// Parses attribute list like `NAME=value,NAME="quoted, value"`
+/// of the tag at `line`, unquoting quoted values.
+fn parse_attributes( list : &str, line : usize ) -> Result< Vec< ( String, String ) >, HlsError >
+{
+  let syntax = | message : &str | HlsError::Syntax { line, message : message.to_string() };
+  let mut result = vec![];
+  let mut rest = list.trim();
+
+  while !rest.is_empty()
+  {
+    let ( name, tail ) = rest.split_once( '=' ).ok_or_else( || syntax( "expected `NAME=value`" ) )?;
+    let ( value, tail ) = match tail.strip_prefix( '"' )
+    {
+      Some( quoted ) =>
+      {
+        let end = quoted.find( '"' ).ok_or_else( || syntax( "unterminated quoted string" ) )?;
+        ( &quoted[ .. end ], &quoted[ end + 1 .. ] )
+      }
+      None => tail.split_at( tail.find( ',' ).unwrap_or( tail.len() ) ),
+    };
+    result.push( ( name.trim().to_string(), value.to_string() ) );
+    rest = match tail.strip_prefix( ',' )
+    {
+      Some( tail ) => tail,
+      None if tail.is_empty() => tail,
+      None => return Err( syntax( "expected `,` between attributes" ) ),
+    };
+  }
+
+  Ok( result )
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ parse_attributes, MasterPlaylist };
+  use crate::{ HlsError, Rendition, SelectionOptions, Value::* };
+
+  const PLAYLIST : &str = "\
+#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"audio.m3u8\"
+
+#EXT-X-STREAM-INF:BANDWIDTH=4500000,RESOLUTION=1280x720,FRAME-RATE=59.940,CODECS=\"avc1.640020,mp4a.40.2\",AUDIO=\"aac\"
+720p60.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360,CODECS=\"avc1.4d401e,mp4a.40.2\",AUDIO=\"aac\"
+# comment
+360p.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=2500000,RESOLUTION=1280x720,FRAME-RATE=29.970,AUDIO=\"aac\"
+720p30.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS=\"mp4a.40.2\",AUDIO=\"aac\"
+audio-only.m3u8
+";
+
+  #[ test ]
+  fn test_parse()
+  {
+    let playlist = MasterPlaylist::parse( PLAYLIST ).unwrap();
+    assert_eq!( playlist.variants.len(), 4 );
+    assert_eq!
+    (
+      playlist.variants[ 0 ].rendition,
+      Some
+      (
+        Rendition
+        {
+          width : 1280,
+          height : 720,
+          fps : 60,
+          bitrate : 4500,
+          codec : "avc1.640020,mp4a.40.2".to_string(),
+        }
+      ),
+    );
+    assert_eq!( playlist.variants[ 0 ].line, 5 );
+    assert_eq!( playlist.variants[ 0 ].attribute( "AUDIO" ), Some( "aac" ) );
+    assert_eq!( playlist.variants[ 1 ].uri, "360p.m3u8" );
+    assert_eq!( playlist.variants[ 3 ].rendition, None );
+    assert_eq!( playlist.variants[ 3 ].bandwidth, 64000 );
+  }
+
+  #[ test ]
+  fn test_available()
+  {
+    let playlist = MasterPlaylist::parse( PLAYLIST ).unwrap();
+    assert_eq!( playlist.available(), vec![ 360, 720 ] );
+    assert_eq!( playlist.uris( &[ 720 ] ), vec![ "720p60.m3u8", "720p30.m3u8" ] );
+    assert_eq!( playlist.uris( &[ 1080 ] ), Vec::< &str >::new() );
+  }
+
+  #[ test ]
+  fn test_errors()
+  {
+    assert_eq!( MasterPlaylist::parse( "#EXT-X-VERSION:6\n" ), Err( HlsError::MissingHeader ) );
+    assert_eq!
+    (
+      MasterPlaylist::parse( "#EXTM3U\n#EXT-X-STREAM-INF:RESOLUTION=640x360\na.m3u8\n" ),
+      Err( HlsError::MissingAttribute { line : 2, name : "BANDWIDTH" } ),
+    );
+    assert_eq!
+    (
+      MasterPlaylist::parse( "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1\n#EXT-X-STREAM-INF:BANDWIDTH=2\nb.m3u8\n" ),
+      Err( HlsError::MissingUri { line : 2 } ),
+    );
+    assert_eq!
+    (
+      MasterPlaylist::parse( "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1,RESOLUTION=640\na.m3u8\n" ),
+      Err( HlsError::Syntax { line : 2, message : "invalid `RESOLUTION`".to_string() } ),
+    );
+  }
+
+  #[ test ]
+  fn test_attributes()
+  {
+    assert_eq!
+    (
+      parse_attributes( "A=1,B=\"x, y\",C=0x1F", 1 ),
+      Ok( vec![ ( "A".to_string(), "1".to_string() ), ( "B".to_string(), "x, y".to_string() ), ( "C".to_string(), "0x1F".to_string() ) ] ),
+    );
+    assert_eq!
+    (
+      parse_attributes( "A=\"x", 3 ),
+      Err( HlsError::Syntax { line : 3, message : "unterminated quoted string".to_string() } ),
+    );
+    assert_eq!
+    (
+      parse_attributes( "A=\"x\"B=1", 3 ),
+      Err( HlsError::Syntax { line : 3, message : "expected `,` between attributes".to_string() } ),
+    );
+  }
+
+  #[ test ]
+  fn test_rewrite()
+  {
+    let playlist = MasterPlaylist::parse
+    (
+      "\
+#EXTM3U
+#EXT-X-INDEPENDENT-SEGMENTS
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"low\",NAME=\"English\",URI=\"audio-64k.m3u8\"
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"high\",NAME=\"English\",URI=\"audio-128k.m3u8\"
+#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"English\",URI=\"subs.m3u8\"
+#EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360,AUDIO=\"low\",SUBTITLES=\"subs\"
+# low quality
+360p.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=5000000,RESOLUTION=1920x1080,AUDIO=\"high\",SUBTITLES=\"subs\"
+1080p.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS=\"mp4a.40.2\"
+audio-only.m3u8
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100000,RESOLUTION=640x360,URI=\"360p-iframes.m3u8\"
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=400000,RESOLUTION=1920x1080,URI=\"1080p-iframes.m3u8\"
+",
+    ).unwrap();
+
+    assert_eq!
+    (
+      playlist.select( &[ AtMost( 720 ) ], &[ Any ], &SelectionOptions::default() ),
+      Ok
+      (
+        "\
+#EXTM3U
+#EXT-X-INDEPENDENT-SEGMENTS
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"low\",NAME=\"English\",URI=\"audio-64k.m3u8\"
+#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"English\",URI=\"subs.m3u8\"
+#EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360,AUDIO=\"low\",SUBTITLES=\"subs\"
+# low quality
+360p.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS=\"mp4a.40.2\"
+audio-only.m3u8
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100000,RESOLUTION=640x360,URI=\"360p-iframes.m3u8\"
+".to_string()
+      ),
+    );
+
+    let rewritten = MasterPlaylist::parse( &playlist.rewrite( &[ 1080 ] ) ).unwrap();
+    let uris : Vec< _ > = rewritten.variants.iter().map( | x | x.uri.as_str() ).collect();
+    assert_eq!( uris, vec![ "1080p.m3u8", "audio-only.m3u8" ] );
+    assert!( !playlist.rewrite( &[ 1080 ] ).contains( "low" ) );
+
+    let audio = MasterPlaylist::parse( &playlist.rewrite( &[] ) ).unwrap();
+    assert_eq!( audio.variants.len(), 1 );
+    assert_eq!( audio.variants[ 0 ].uri, "audio-only.m3u8" );
+  }
+}
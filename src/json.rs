@@ -0,0 +1,610 @@
+use crate::{ JsonError, Value };
+
+/// Parsed JSON document. Numbers keep their original text
+/// so they can be converted into any numeric type.
+#[ derive( Debug, PartialEq, Clone ) ]
+pub( crate ) enum Json
+{
+  Null,
+  Bool( bool ),
+  Number( String ),
+  String( String ),
+  Array( Vec< Json > ),
+  Object( Vec< ( String, Json ) > ),
+}
+
+impl Json
+{
+  /// Parses `text` which must contain exactly one JSON value.
+  pub( crate ) fn parse( text : &str ) -> Result< Json, JsonError >
+  {
+    let mut parser = Parser { bytes : text.as_bytes(), position : 0, depth : 0 };
+    let result = parser.value()?;
+    parser.whitespace();
+    if parser.position < parser.bytes.len()
+    {
+      return Err( parser.error() );
+    }
+    Ok( result )
+  }
+
+  /// Returns field `name` of an object at `path`.
+  pub( crate ) fn field( &self, name : &str, path : &str ) -> Result< &Json, JsonError >
+  {
+    match self
+    {
+      Json::Object( fields ) => fields
+      .iter()
+      .find( | ( key, _ ) | key == name )
+      .map( | ( _, value ) | value )
+      .ok_or_else( || JsonError::MissingField { path : format!( "{}.{}", path, name ) } ),
+      _ => Err( JsonError::InvalidValue { path : path.to_string() } ),
+    }
+  }
+
+  /// Converts array at `path` element by element with `convert`.
+  pub( crate ) fn array< T >
+  (
+    &self,
+    path : &str,
+    convert : impl Fn( &Json, &str ) -> Result< T, JsonError >,
+  ) -> Result< Vec< T >, JsonError >
+  {
+    match self
+    {
+      Json::Array( items ) => items
+      .iter()
+      .enumerate()
+      .map( | ( index, item ) | convert( item, &format!( "{}[{}]", path, index ) ) )
+      .collect(),
+      _ => Err( JsonError::InvalidValue { path : path.to_string() } ),
+    }
+  }
+
+  /// Converts array in field `name` of an object at `path`
+  /// element by element with `convert`.
+  pub( crate ) fn array_field< T >
+  (
+    &self,
+    name : &str,
+    path : &str,
+    convert : impl Fn( &Json, &str ) -> Result< T, JsonError >,
+  ) -> Result< Vec< T >, JsonError >
+  {
+    self.field( name, path )?.array( &format!( "{}.{}", path, name ), convert )
+  }
+}
+
+impl std::fmt::Display for Json
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      Json::Null => write!( f, "null" ),
+      Json::Bool( b ) => write!( f, "{}", b ),
+      Json::Number( n ) => write!( f, "{}", n ),
+      Json::String( s ) => write_string( f, s ),
+      Json::Array( items ) =>
+      {
+        write!( f, "[" )?;
+        for ( index, item ) in items.iter().enumerate()
+        {
+          if index > 0
+          {
+            write!( f, "," )?;
+          }
+          write!( f, "{}", item )?;
+        }
+        write!( f, "]" )
+      }
+      Json::Object( fields ) =>
+      {
+        write!( f, "{{" )?;
+        for ( index, ( key, value ) ) in fields.iter().enumerate()
+        {
+          if index > 0
+          {
+            write!( f, "," )?;
+          }
+          write_string( f, key )?;
+          write!( f, ":{}", value )?;
+        }
+        write!( f, "}}" )
+      }
+    }
+  }
+}
+
+/// Writes `s` as quoted JSON string.
+fn write_string( f : &mut std::fmt::Formatter< '_ >, s : &str ) -> std::fmt::Result
+{
+  write!( f, "\"" )?;
+  for c in s.chars()
+  {
+    match c
+    {
+      '"' => write!( f, "\\\"" )?,
+      '\\' => write!( f, "\\\\" )?,
+      '\n' => write!( f, "\\n" )?,
+      '\r' => write!( f, "\\r" )?,
+      '\t' => write!( f, "\\t" )?,
+      c if c.is_control() => write!( f, "\\u{:04x}", c as u32 )?,
+      c => write!( f, "{}", c )?,
+    }
+  }
+  write!( f, "\"" )
+}
+
+/// Returns `true` if `text` is a valid JSON number.
+fn is_number( text : &str ) -> bool
+{
+  let mut parser = Parser { bytes : text.as_bytes(), position : 0, depth : 0 };
+  parser.number().is_ok() && parser.position == text.len()
+}
+
+/// Converts number into JSON. Its `Display` output becomes a JSON
+/// number if it is a valid one, e.g. `720`, or a string otherwise,
+/// e.g. `"720p"`.
+pub( crate ) fn number_to_json< T : std::fmt::Display >( n : &T ) -> Json
+{
+  let text = n.to_string();
+  if is_number( &text )
+  {
+    Json::Number( text )
+  }
+  else
+  {
+    Json::String( text )
+  }
+}
+
+/// Converts JSON number, or string which is not
+/// a valid JSON number, at `path` into `T`.
+pub( crate ) fn number_from_json< T : std::str::FromStr >( json : &Json, path : &str ) -> Result< T, JsonError >
+{
+  match json
+  {
+    Json::Number( n ) => n.parse().map_err( | _ | JsonError::InvalidValue { path : path.to_string() } ),
+    Json::String( s ) if !is_number( s ) => s.parse().map_err( | _ | JsonError::InvalidValue { path : path.to_string() } ),
+    _ => Err( JsonError::InvalidValue { path : path.to_string() } ),
+  }
+}
+
+/// Converts value into JSON. Numbers are converted with
+/// [`number_to_json`], other values become strings in text
+/// syntax, e.g. `"any"` or `"360..=1080"`.
+pub( crate ) fn value_to_json< T : std::fmt::Display >( value : &Value< T > ) -> Json
+{
+  match value
+  {
+    Value::Number( n ) => number_to_json( n ),
+    _ => Json::String( value.to_string() ),
+  }
+}
+
+/// Converts JSON number or string in text syntax other than
+/// a plain JSON number at `path` into value.
+pub( crate ) fn value_from_json< T : std::str::FromStr >( json : &Json, path : &str ) -> Result< Value< T >, JsonError >
+{
+  match json
+  {
+    Json::String( s ) => match s.parse()
+    {
+      Ok( Value::Number( _ ) ) if is_number( s ) => Err( JsonError::InvalidValue { path : path.to_string() } ),
+      Err( _ ) => Err( JsonError::InvalidValue { path : path.to_string() } ),
+      Ok( value ) => Ok( value ),
+    },
+    _ => number_from_json( json, path ).map( Value::Number ),
+  }
+}
+
+impl< T : std::fmt::Display > Value< T >
+{
+  /// Returns JSON representation of the value: a number,
+  /// or a string in text syntax such as `"any"`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use task_rust::Value;
+  /// assert_eq!( Value::Number( 720 ).to_json(), "720" );
+  /// assert_eq!( Value::< i32 >::Any.to_json(), "\"any\"" );
+  /// assert_eq!( Value::range( 360 ..= 1080 ).to_json(), "\"360..=1080\"" );
+  /// ```
+  pub fn to_json( &self ) -> String
+  {
+    value_to_json( self ).to_string()
+  }
+}
+
+impl< T : std::str::FromStr > Value< T >
+{
+  /// Reads value from its JSON representation.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JsonError`] if `json` is not a number
+  /// or a string in text syntax.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use task_rust::{ JsonError, Value };
+  /// assert_eq!( Value::from_json( " 720 " ), Ok( Value::Number( 720 ) ) );
+  /// assert_eq!( Value::< i32 >::from_json( "\"any\"" ), Ok( Value::Any ) );
+  /// assert_eq!( Value::from_json( "\"360..\"" ), Ok( Value::range( 360 .. ) ) );
+  /// assert_eq!
+  /// (
+  ///   Value::< i32 >::from_json( "\"all\"" ),
+  ///   Err( JsonError::InvalidValue { path : "$".to_string() } ),
+  /// );
+  /// ```
+  pub fn from_json( json : &str ) -> Result< Self, JsonError >
+  {
+    value_from_json( &Json::parse( json )?, "$" )
+  }
+}
+
+/// Maximal nesting of arrays and objects accepted by [`Parser`],
+/// so that deeply nested input cannot overflow the stack.
+const MAX_DEPTH : usize = 128;
+
+/// Recursive descent JSON parser over UTF-8 bytes.
+struct Parser< 'a >
+{
+  bytes : &'a [ u8 ],
+  position : usize,
+  /// Number of arrays and objects being parsed.
+  depth : usize,
+}
+
+impl Parser< '_ >
+{
+  fn error( &self ) -> JsonError
+  {
+    JsonError::Syntax { offset : self.position }
+  }
+
+  fn peek( &self ) -> Option< u8 >
+  {
+    self.bytes.get( self.position ).copied()
+  }
+
+  fn whitespace( &mut self )
+  {
+    while matches!( self.peek(), Some( b' ' | b'\t' | b'\n' | b'\r' ) )
+    {
+      self.position += 1;
+    }
+  }
+
+  fn expect( &mut self, byte : u8 ) -> Result< (), JsonError >
+  {
+    self.whitespace();
+    if self.peek() == Some( byte )
+    {
+      self.position += 1;
+      Ok( () )
+    }
+    else
+    {
+      Err( self.error() )
+    }
+  }
+
+  fn literal( &mut self, text : &str, json : Json ) -> Result< Json, JsonError >
+  {
+    if self.bytes[ self.position .. ].starts_with( text.as_bytes() )
+    {
+      self.position += text.len();
+      Ok( json )
+    }
+    else
+    {
+      Err( self.error() )
+    }
+  }
+
+  fn value( &mut self ) -> Result< Json, JsonError >
+  {
+    self.whitespace();
+    match self.peek()
+    {
+      Some( b'n' ) => self.literal( "null", Json::Null ),
+      Some( b't' ) => self.literal( "true", Json::Bool( true ) ),
+      Some( b'f' ) => self.literal( "false", Json::Bool( false ) ),
+      Some( b'"' ) => self.string().map( Json::String ),
+      Some( b'[' ) => self.array(),
+      Some( b'{' ) => self.object(),
+      Some( b'-' | b'0' ..= b'9' ) => self.number(),
+      _ => Err( self.error() ),
+    }
+  }
+
+  /// Parses items of a sequence enclosed in `open` and `close`,
+  /// failing if it is nested deeper than [`MAX_DEPTH`].
+  fn sequence< T >
+  (
+    &mut self,
+    open : u8,
+    close : u8,
+    item : impl FnMut( &mut Self ) -> Result< T, JsonError >,
+  ) -> Result< Vec< T >, JsonError >
+  {
+    self.expect( open )?;
+    if self.depth == MAX_DEPTH
+    {
+      return Err( JsonError::Syntax { offset : self.position - 1 } );
+    }
+    self.depth += 1;
+    let result = self.items( close, item );
+    self.depth -= 1;
+    result
+  }
+
+  /// Parses items of a sequence after its opening byte.
+  fn items< T >
+  (
+    &mut self,
+    close : u8,
+    mut item : impl FnMut( &mut Self ) -> Result< T, JsonError >,
+  ) -> Result< Vec< T >, JsonError >
+  {
+    let mut result = vec![];
+    self.whitespace();
+    if self.peek() == Some( close )
+    {
+      self.position += 1;
+      return Ok( result );
+    }
+    loop
+    {
+      result.push( item( self )? );
+      self.whitespace();
+      match self.peek()
+      {
+        Some( b',' ) => self.position += 1,
+        Some( byte ) if byte == close =>
+        {
+          self.position += 1;
+          return Ok( result );
+        }
+        _ => return Err( self.error() ),
+      }
+    }
+  }
+
+  fn array( &mut self ) -> Result< Json, JsonError >
+  {
+    self.sequence( b'[', b']', Self::value ).map( Json::Array )
+  }
+
+  fn object( &mut self ) -> Result< Json, JsonError >
+  {
+    self
+    .sequence
+    (
+      b'{',
+      b'}',
+      | parser |
+      {
+        parser.whitespace();
+        let key = parser.string()?;
+        parser.expect( b':' )?;
+        Ok( ( key, parser.value()? ) )
+      },
+    )
+    .map( Json::Object )
+  }
+
+  fn number( &mut self ) -> Result< Json, JsonError >
+  {
+    let start = self.position;
+    let digits = | parser : &mut Self | -> Result< (), JsonError >
+    {
+      let start = parser.position;
+      while matches!( parser.peek(), Some( b'0' ..= b'9' ) )
+      {
+        parser.position += 1;
+      }
+      if parser.position == start
+      {
+        Err( parser.error() )
+      }
+      else
+      {
+        Ok( () )
+      }
+    };
+
+    if self.peek() == Some( b'-' )
+    {
+      self.position += 1;
+    }
+    if self.peek() == Some( b'0' )
+    {
+      self.position += 1;
+    }
+    else
+    {
+      digits( self )?;
+    }
+    if self.peek() == Some( b'.' )
+    {
+      self.position += 1;
+      digits( self )?;
+    }
+    if matches!( self.peek(), Some( b'e' | b'E' ) )
+    {
+      self.position += 1;
+      if matches!( self.peek(), Some( b'+' | b'-' ) )
+      {
+        self.position += 1;
+      }
+      digits( self )?;
+    }
+
+    Ok( Json::Number( String::from_utf8_lossy( &self.bytes[ start .. self.position ] ).into_owned() ) )
+  }
+
+  fn hex( &mut self ) -> Result< u32, JsonError >
+  {
+    let digits = self.bytes.get( self.position .. self.position + 4 ).ok_or( self.error() )?;
+    let code = std::str::from_utf8( digits )
+    .ok()
+    .filter( | x | x.bytes().all( | b | b.is_ascii_hexdigit() ) )
+    .and_then( | x | u32::from_str_radix( x, 16 ).ok() )
+    .ok_or( self.error() )?;
+    self.position += 4;
+    Ok( code )
+  }
+
+  /// Parses escape sequence following a backslash.
+  fn escape( &mut self ) -> Result< char, JsonError >
+  {
+    let start = self.position;
+    self.position += 1;
+    let result = match self.bytes.get( start )
+    {
+      Some( b'"' ) => Some( '"' ),
+      Some( b'\\' ) => Some( '\\' ),
+      Some( b'/' ) => Some( '/' ),
+      Some( b'b' ) => Some( '\u{8}' ),
+      Some( b'f' ) => Some( '\u{c}' ),
+      Some( b'n' ) => Some( '\n' ),
+      Some( b'r' ) => Some( '\r' ),
+      Some( b't' ) => Some( '\t' ),
+      Some( b'u' ) =>
+      {
+        let mut code = self.hex()?;
+        if ( 0xd800 .. 0xdc00 ).contains( &code ) && self.bytes[ self.position .. ].starts_with( b"\\u" )
+        {
+          self.position += 2;
+          let low = self.hex()?;
+          if ( 0xdc00 .. 0xe000 ).contains( &low )
+          {
+            code = 0x10000 + ( ( code - 0xd800 ) << 10 ) + ( low - 0xdc00 );
+          }
+        }
+        char::from_u32( code )
+      }
+      _ => None,
+    };
+    result.ok_or( JsonError::Syntax { offset : start } )
+  }
+
+  fn string( &mut self ) -> Result< String, JsonError >
+  {
+    self.expect( b'"' )?;
+    let mut result = vec![];
+
+    loop
+    {
+      match self.peek()
+      {
+        Some( b'"' ) =>
+        {
+          self.position += 1;
+          return String::from_utf8( result ).map_err( | _ | self.error() );
+        }
+        Some( b'\\' ) =>
+        {
+          self.position += 1;
+          let escaped = self.escape()?;
+          result.extend_from_slice( escaped.encode_utf8( &mut [ 0; 4 ] ).as_bytes() );
+        }
+        Some( byte ) if byte >= 0x20 =>
+        {
+          self.position += 1;
+          result.push( byte );
+        }
+        _ => return Err( self.error() ),
+      }
+    }
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ number_from_json, number_to_json, Json, MAX_DEPTH };
+  use crate::{ JsonError, Resolution, Value };
+
+  #[ test ]
+  fn test_parse()
+  {
+    assert_eq!
+    (
+      Json::parse( r#" { "a" : [ 1, -2.5e3, "x\né😀" ], "b" : { }, "c" : [ true, false, null ] } "# ),
+      Ok
+      (
+        Json::Object
+        (
+          vec!
+          [
+            (
+              "a".to_string(),
+              Json::Array
+              (
+                vec!
+                [
+                  Json::Number( "1".to_string() ),
+                  Json::Number( "-2.5e3".to_string() ),
+                  Json::String( "x\né😀".to_string() ),
+                ]
+              ),
+            ),
+            ( "b".to_string(), Json::Object( vec![] ) ),
+            ( "c".to_string(), Json::Array( vec![ Json::Bool( true ), Json::Bool( false ), Json::Null ] ) ),
+          ]
+        )
+      ),
+    );
+  }
+
+  #[ test ]
+  fn test_parse_errors()
+  {
+    assert_eq!( Json::parse( "" ), Err( JsonError::Syntax { offset : 0 } ) );
+    assert_eq!( Json::parse( "[ 1, ]" ), Err( JsonError::Syntax { offset : 5 } ) );
+    assert_eq!( Json::parse( "{ \"a\" 1 }" ), Err( JsonError::Syntax { offset : 6 } ) );
+    assert_eq!( Json::parse( "01" ), Err( JsonError::Syntax { offset : 1 } ) );
+    assert_eq!( Json::parse( "\"abc" ), Err( JsonError::Syntax { offset : 4 } ) );
+    assert_eq!( Json::parse( "[ 1 ] 2" ), Err( JsonError::Syntax { offset : 6 } ) );
+  }
+
+  #[ test ]
+  fn test_depth()
+  {
+    let nested = | depth : usize | format!( "{}{}", "[".repeat( depth ), "]".repeat( depth ) );
+    assert!( Json::parse( &nested( MAX_DEPTH ) ).is_ok() );
+    assert_eq!( Json::parse( &nested( MAX_DEPTH + 1 ) ), Err( JsonError::Syntax { offset : MAX_DEPTH } ) );
+    assert_eq!( Json::parse( &"[".repeat( 200_000 ) ), Err( JsonError::Syntax { offset : MAX_DEPTH } ) );
+    assert_eq!( Json::parse( &"{\"a\":".repeat( 200_000 ) ), Err( JsonError::Syntax { offset : MAX_DEPTH * 5 } ) );
+  }
+
+  #[ test ]
+  fn test_non_numeric_numbers()
+  {
+    let hd : Resolution = "720p".parse().unwrap();
+    assert_eq!( number_to_json( &hd ).to_string(), "\"720p\"" );
+    assert_eq!( number_to_json( &720 ).to_string(), "720" );
+    assert_eq!( number_from_json::< Resolution >( &number_to_json( &hd ), "$" ).map( | x | x.to_string() ), Ok( "720p".to_string() ) );
+    assert_eq!
+    (
+      number_from_json::< i32 >( &Json::String( "720".to_string() ), "$" ),
+      Err( JsonError::InvalidValue { path : "$".to_string() } ),
+    );
+    assert_eq!( Value::Number( hd ).to_json(), "\"720p\"" );
+    assert_eq!( Value::< Resolution >::from_json( "\"720p\"" ).map( | x | x.to_string() ), Ok( "720p".to_string() ) );
+    assert_eq!( Value::< i32 >::from_json( "\"720\"" ), Err( JsonError::InvalidValue { path : "$".to_string() } ) );
+  }
+
+  #[ test ]
+  fn test_round_trip()
+  {
+    let text = r#"{"a":[1,"q\"\\\t\u0001"],"b":null}"#;
+    assert_eq!( Json::parse( text ).unwrap().to_string(), text );
+  }
+}
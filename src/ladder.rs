@@ -0,0 +1,173 @@
+use crate::{ check_sorted, Argument, LadderError, NumberList, SelectionError };
+
+/// Built-in ladders as `( name, version, heights )`.
+const BUILTIN : [ ( &str, u32, &[ u32 ] ); 7 ] =
+[
+  ( "vod", 1, &[ 240, 360, 480, 720, 1080 ] ),
+  ( "vod", 2, &[ 360, 540, 720, 1080, 1440, 2160 ] ),
+  ( "live", 1, &[ 360, 540, 720, 1080 ] ),
+  ( "mobile", 1, &[ 144, 240, 360, 480 ] ),
+  ( "apple-hls", 1, &[ 234, 360, 432, 540, 720, 1080 ] ),
+  ( "apple-hls", 2, &[ 360, 432, 540, 720, 1080, 1440, 2160 ] ),
+  ( "uhd", 1, &[ 720, 1080, 1440, 2160 ] ),
+];
+
+/// Named and versioned set of heights an encoder produces,
+/// usable as `available`.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Ladder
+{
+  pub name : String,
+  pub version : u32,
+  /// Sorted heights of the ladder.
+  pub heights : Vec< u32 >,
+}
+
+/// Collection of [`Ladder`]s looked up by `name@version`, or by
+/// `name` for its latest version. The default catalog contains
+/// the built-in ladders `vod`, `live`, `mobile`, `apple-hls`
+/// and `uhd`.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt, Ladder, LadderCatalog, Value::* };
+/// let mut catalog = LadderCatalog::default();
+/// assert_eq!( catalog.get( "vod@1" ).unwrap().heights, vec![ 240, 360, 480, 720, 1080 ] );
+/// assert_eq!( catalog.get( "vod" ).unwrap().version, 2 );
+///
+/// catalog.register( Ladder { name : "studio".to_string(), version : 1, heights : vec![ 480, 1080 ] } ).unwrap();
+/// let studio = catalog.get( "studio" ).unwrap();
+/// assert_eq!( attempt( &studio.heights, &[ Any ], &[ Number( 720 ) ] ), Ok( vec![ 1080 ] ) );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct LadderCatalog
+{
+  ladders : Vec< Ladder >,
+}
+
+impl Default for LadderCatalog
+{
+  fn default() -> Self
+  {
+    let ladders = BUILTIN
+    .iter()
+    .map( | ( name, version, heights ) | Ladder { name : name.to_string(), version : *version, heights : heights.to_vec() } )
+    .collect();
+    Self { ladders }
+  }
+}
+
+impl LadderCatalog
+{
+  /// Creates catalog without any ladders.
+  pub fn empty() -> Self
+  {
+    Self { ladders : vec![] }
+  }
+
+  /// Adds `ladder` to the catalog.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LadderError`] if the name is invalid, the heights are
+  /// not sorted or the same version is already registered.
+  pub fn register( &mut self, ladder : Ladder ) -> Result< (), LadderError >
+  {
+    if ladder.name.is_empty() || ladder.name.contains( '@' )
+    {
+      return Err( LadderError::InvalidName { name : ladder.name } );
+    }
+    if let Err( SelectionError::Unsorted { index, .. } ) = check_sorted( &ladder.heights, Argument::Available )
+    {
+      return Err( LadderError::Unsorted { name : ladder.name, index } );
+    }
+    if self.ladders.iter().any( | x | x.name == ladder.name && x.version == ladder.version )
+    {
+      return Err( LadderError::Duplicate { name : ladder.name, version : ladder.version } );
+    }
+    self.ladders.push( ladder );
+    Ok( () )
+  }
+
+  /// Returns ladder named `name@version`, or the latest version of
+  /// ladder `name` if no version is given.
+  pub fn get( &self, name : &str ) -> Option< &Ladder >
+  {
+    match name.split_once( '@' )
+    {
+      Some( ( name, version ) ) =>
+      {
+        let version = version.parse().ok()?;
+        self.ladders.iter().find( | x | x.name == name && x.version == version )
+      }
+      None => self.ladders.iter().filter( | x | x.name == name ).max_by_key( | x | x.version ),
+    }
+  }
+
+  /// Returns every ladder, ordered by name and version.
+  pub fn ladders( &self ) -> Vec< &Ladder >
+  {
+    let mut ladders : Vec< _ > = self.ladders.iter().collect();
+    ladders.sort_by( | a, b | ( &a.name, a.version ).cmp( &( &b.name, b.version ) ) );
+    ladders
+  }
+}
+
+impl std::fmt::Display for Ladder
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    write!( f, "{}@{} : {}", self.name, self.version, NumberList( self.heights.clone() ) )
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ Ladder, LadderCatalog };
+  use crate::LadderError;
+
+  fn ladder( name : &str, version : u32, heights : &[ u32 ] ) -> Ladder
+  {
+    Ladder { name : name.to_string(), version, heights : heights.to_vec() }
+  }
+
+  #[ test ]
+  fn test_builtin()
+  {
+    let catalog = LadderCatalog::default();
+    for ladder in catalog.ladders()
+    {
+      assert_eq!( catalog.get( &format!( "{}@{}", ladder.name, ladder.version ) ), Some( ladder ) );
+    }
+    assert_eq!( catalog.get( "apple-hls" ).unwrap().to_string(), "apple-hls@2 : 360, 432, 540, 720, 1080, 1440, 2160" );
+    assert_eq!( catalog.get( "vod@3" ), None );
+    assert_eq!( catalog.get( "vod@x" ), None );
+    assert_eq!( catalog.get( "bogus" ), None );
+  }
+
+  #[ test ]
+  fn test_register()
+  {
+    let mut catalog = LadderCatalog::empty();
+    assert_eq!( catalog.register( ladder( "a", 2, &[ 360 ] ) ), Ok( () ) );
+    assert_eq!( catalog.register( ladder( "a", 1, &[ 240 ] ) ), Ok( () ) );
+    assert_eq!( catalog.get( "a" ), Some( &ladder( "a", 2, &[ 360 ] ) ) );
+    assert_eq!
+    (
+      catalog.register( ladder( "a", 1, &[ 480 ] ) ),
+      Err( LadderError::Duplicate { name : "a".to_string(), version : 1 } ),
+    );
+    assert_eq!
+    (
+      catalog.register( ladder( "a@3", 1, &[ 480 ] ) ),
+      Err( LadderError::InvalidName { name : "a@3".to_string() } ),
+    );
+    assert_eq!
+    (
+      catalog.register( ladder( "b", 1, &[ 480, 360 ] ) ),
+      Err( LadderError::Unsorted { name : "b".to_string(), index : 1 } ),
+    );
+  }
+}
@@ -0,0 +1,74 @@
+//! Selection of numeric values (e.g. video resolutions) out of an
+//! `available` set, restricted by an `allowed` set and steered towards
+//! a `preferred` set.
+//!
+//! # Examples
+//!
+//! ```
+//! use task_rust::{ attempt, Value::* };
+//!
+//! assert_eq!
+//! (
+//!   attempt
+//!   (
+//!     &[ 240, 360, 720 ],
+//!     &[ Number( 360 ), Any ],
+//!     &[ Number( 360 ), Number( 720 ) ],
+//!   ),
+//!   Ok( vec![ 360, 720 ] ),
+//! );
+//! ```
+
+mod batch;
+mod dash;
+mod diagnose;
+mod error;
+mod explain;
+mod fallback;
+mod hls;
+mod json;
+mod ladder;
+mod order;
+mod rendition;
+mod request;
+mod resolution;
+mod scenario;
+mod select;
+mod selector;
+mod sorted;
+mod strategy;
+mod syntax;
+mod throughput;
+mod value;
+
+pub use batch::{ run_batch, BatchSummary };
+pub use dash::{ AdaptationSet, Mpd, Representation };
+pub use diagnose::{ diagnose_empty, Change, Diagnosis, EmptyCause, Suggestion };
+pub use error::{ Argument, DashError, HlsError, JsonError, LadderError, ParseError, ScenarioError, SelectionError };
+pub use explain::{ attempt_explained, AvailableTrace, Decision, Explanation, Outcome, PreferredTrace };
+pub use fallback::{ Fallback, FallbackPolicy, FallbackSelection };
+pub use hls::{ MasterPlaylist, Variant };
+pub use ladder::{ Ladder, LadderCatalog };
+pub use order::ResultOrder;
+pub use rendition::{ Dimension, Rendition, RenditionSelector, RenditionValues };
+pub use request::{ SelectionRequest, SelectionResponse };
+pub use resolution::Resolution;
+pub use scenario::{ load_scenarios, Scenario, ScenarioMismatch };
+pub use select::
+{
+  attempt,
+  attempt_checked,
+  attempt_normalized,
+  attempt_sorted,
+  attempt_with,
+  filter_allowed,
+  find_preferred,
+  find_preferred_with,
+  SelectionOptions,
+};
+pub use selector::{ Hysteresis, Selector };
+pub use sorted::{ check_sorted, check_sorted_values, SortedSet, SortedValues };
+pub use strategy::{ Distance, MatchStrategy, Tie };
+pub use syntax::{ NumberList, ValueList };
+pub use throughput::{ Throughput, ThroughputSelection };
+pub use value::Value;
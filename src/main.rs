@@ -1,500 +1,827 @@
-#[ derive( PartialEq, Eq, Clone ) ]
-/// Represents value which is either `any` or some number
-enum Value
-{
-  Number( i32 ),
-  Any,
-}
+use std::process::ExitCode;
+use task_rust::{ attempt_explained, attempt_with, check_sorted, check_sorted_values, diagnose_empty, load_scenarios, run_batch };
+use task_rust::{ Argument, FallbackPolicy, FallbackSelection, LadderCatalog, MasterPlaylist, Mpd, NumberList, ParseError, Resolution, Scenario, SelectionOptions, Value, ValueList };
 
-impl std::fmt::Debug for Value
+// Defines `SCENARIOS`, every scenario of `fixtures/scenarios` as
+// `( name, text )`, generated by `build.rs`.
+include!( concat!( env!( "OUT_DIR" ), "/scenarios.rs" ) );
+
+/// Prints out every scenario in `dir`, or the bundled ones, with its
+/// result, or a diff if the result differs from the expected one.
+fn demo( dir : Option< &str > ) -> ExitCode
 {
-  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  let scenarios = match dir
   {
-    match self
+    Some( dir ) => load_scenarios( dir ),
+    None => SCENARIOS.iter().map( | ( name, text ) | Scenario::parse( name, text ) ).collect(),
+  };
+  let scenarios = match scenarios
+  {
+    Ok( scenarios ) => scenarios,
+    Err( error ) =>
+    {
+      eprintln!( "error: {}", error );
+      return ExitCode::from( 2 );
+    }
+  };
+
+  let mut failed = 0;
+  for scenario in &scenarios
+  {
+    match scenario.check()
     {
-      Value::Number( n ) => write!( f, "{}", n ),
-      Value::Any => write!( f, "`any`" ),
+      Ok( () ) => println!( "# {}\n{}", scenario.name, scenario ),
+      Err( mismatch ) =>
+      {
+        failed += 1;
+        println!( "{}", mismatch );
+      }
     }
   }
+
+  if failed == 0
+  {
+    ExitCode::SUCCESS
+  }
+  else
+  {
+    eprintln!( "error: {} of {} scenarios failed", failed, scenarios.len() );
+    ExitCode::from( 1 )
+  }
 }
 
-impl Value
+const USAGE : &str = "\
+Usage:
+  task_rust (--available <LIST> | --ladder <NAME>) [--allowed <LIST>] [--preferred <LIST>]
+            [--strategy <STRATEGY>] [--order <ORDER>] [--fallback <CHAIN>] [--explain]
+  task_rust hls <FILE> [--allowed <LIST>] [--preferred <LIST>] [--strategy <STRATEGY>]
+  task_rust dash <FILE> [--allowed <LIST>] [--preferred <LIST>] [--strategy <STRATEGY>]
+  task_rust batch [FILE]
+  task_rust demo [DIR]
+  task_rust ladders
+  task_rust --help
+
+Lists are comma separated sorted numbers, optionally in brackets,
+e.g. `240,360` or `[ 360, any ]`. `any` matches every number,
+ranges like `360..=1080` and comparisons like `>=720`, `<=1080`
+or `!480` are accepted in `--allowed`. Exclusions with `!` win
+over every other value, including `any`.
+`--allowed` and `--preferred` default to `any`.
+
+Numbers are heights and may also be written as resolution labels:
+`720p`, `1080p60`, `1920x1080`, `SD`, `HD`, `FHD`, `UHD`, `4K` or
+`8K`, e.g. `--allowed HD..=4K`. Results are written in the style
+of `--available`. Values of the same height are told apart by their
+frame rate, so `720p30` and `720p60` are distinct, while a value
+without frame rate stands for every frame rate of its height, so
+`<=FHD` includes `1080p60`. `hls` and `dash` only compare heights
+and ignore frame rates.
+
+`--ladder` uses a built-in ladder as `--available`, named like
+`vod@1`, or like `vod` for its latest version. `ladders` lists them.
+
+`--strategy` chooses how a preferred value is matched when it is not
+available: `round-up` (default, otherwise the largest value),
+`round-down` (otherwise the smallest value), `nearest` (ties go up),
+`nearest-down` (ties go down) or `exact` (no match at all).
+
+`--order` sorts the unique result: `preference` (default, in order
+of preferred values), `ascending` or `descending`.
+
+`--fallback` is tried when the result is empty: a `;` separated chain
+of `any` (every value not excluded in `--allowed`), `lowest` (the
+lowest available value not excluded in `--allowed`) or sorted lists
+replacing `--allowed` but its exclusions, e.g. `360..=1080; any; lowest`. The first
+fallback with a non-empty result is reported on standard error.
+
+`--explain` shows why each available value was kept or dropped and
+how each preferred value was matched before the result.
+
+`hls` selects heights out of the variants of HLS master playlist FILE
+and prints the playlist with only the selected variants, variants
+without resolution, e.g. audio only ones, and the media groups they
+use.
+
+`dash` selects heights out of the representations of each adaptation
+set of DASH manifest FILE on its own and prints the manifest with only
+the selected representations. Adaptation sets without heights, e.g.
+audio ones, are kept, and video ones left empty are removed.
+
+`batch` reads JSON Lines requests like
+  {\"available\":[240,720],\"allowed\":[\"any\"],\"preferred\":[1080]}
+from FILE, or from standard input if FILE is absent or `-`, and
+writes one JSON line with the result or error per request.
+
+`demo` runs scenario files from DIR, by default the scenarios of
+`fixtures/scenarios` built into the program, and shows a diff for
+unexpected results.
+
+An empty result is explained on standard error together with a
+change of `--allowed` or `--preferred` that would avoid it.
+
+Exit codes:
+  0  non-empty result, or every batch line or scenario succeeded
+  1  empty result, or some batch lines or scenarios failed
+  2  invalid arguments or unreadable input";
+
+/// Command requested on the command line.
+#[ derive( Debug, PartialEq ) ]
+enum Command
+{
+  Select
+  {
+    available : Vec< Resolution >,
+    allowed : Vec< Value< Resolution > >,
+    preferred : Vec< Value< Resolution > >,
+    options : SelectionOptions,
+    fallback : Option< FallbackPolicy< Resolution > >,
+    explain : bool,
+  },
+  Hls
+  {
+    path : String,
+    allowed : Vec< Value< Resolution > >,
+    preferred : Vec< Value< Resolution > >,
+    options : SelectionOptions,
+  },
+  Dash
+  {
+    path : String,
+    allowed : Vec< Value< Resolution > >,
+    preferred : Vec< Value< Resolution > >,
+    options : SelectionOptions,
+  },
+  Batch
+  {
+    path : Option< String >,
+  },
+  Demo
+  {
+    dir : Option< String >,
+  },
+  Ladders,
+  Help,
+}
+
+/// Error in command line arguments.
+#[ derive( Debug, PartialEq ) ]
+enum ArgsError
 {
-  /// Returns number contained in `Number` consuming `self`.
-  /// 
-  /// # Panics
-  /// 
-  /// Panics if self value equals `Any`
-  /// 
-  /// # Examples
-  /// 
-  /// ```
-  /// let value = Value::Number( 1 );
-  /// assert_eq!
-  /// (
-  ///   value.assume_number(),
-  ///   1
-  /// );
-  /// ```
-  /// 
-  /// ```should_panic
-  /// let value = Value::Any;
-  /// assert_eq! // panics
-  /// (
-  ///   value.assume_number(),
-  ///   1
-  /// );
-  /// ```
-  fn assume_number( self ) -> i32
-  {
-    Option::unwrap(Some(1));
+  UnknownArgument( String ),
+  MissingValue( &'static str ),
+  MissingArgument( &'static str ),
+  DuplicateArgument( &'static str ),
+  ConflictingArguments( &'static str, &'static str ),
+  UnknownLadder( String ),
+  InvalidValue
+  {
+    flag : &'static str,
+    error : ParseError,
+  },
+}
 
+impl std::fmt::Display for ArgsError
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
     match self
     {
-      Value::Number( n ) => n,
-      Value::Any => unreachable!( "Assuming value is a number" ),
+      ArgsError::UnknownArgument( arg ) => write!( f, "unknown argument `{}`", arg ),
+      ArgsError::MissingValue( flag ) => write!( f, "`{}` requires a value", flag ),
+      ArgsError::MissingArgument( flag ) => write!( f, "`{}` is required", flag ),
+      ArgsError::DuplicateArgument( flag ) => write!( f, "`{}` is given more than once", flag ),
+      ArgsError::ConflictingArguments( a, b ) => write!( f, "`{}` cannot be given together with `{}`", a, b ),
+      ArgsError::UnknownLadder( name ) => write!( f, "unknown ladder `{}`", name ),
+      ArgsError::InvalidValue { flag, error } => write!( f, "{} in `{}`", error, flag ),
     }
   }
 }
 
-/// Accepts **sorted** slices of values and returns
-/// vector of a numbers in `available` slice that
-/// present in `allowed` and closest to `preferred`.
-/// 
-/// # Examples
-/// 
-/// ```
-/// # use Value::*;
-/// assert_eq!
-/// (
-///   attempt
-///   (
-///     &[ 240, 360, 720 ],
-///     &[ Number( 360 ), Number( 720 ) ],
-///     &[ Number( 1080 ) ],
-///   ),
-///   vec![ 720 ],
-/// );
-/// ```
-fn attempt( available : &[ i32 ], allowed : &[ Value ], preferred : & [ Value ] ) -> Vec< i32 >
+/// Parses value given to `flag`.
+fn parse_value< L : std::str::FromStr< Err = ParseError > >( flag : &'static str, list : &str ) -> Result< L, ArgsError >
 {
-  find_preferred( filter_allowed( available.to_vec(), allowed.to_vec() ), preferred.to_vec() )
+  list.parse().map_err( | error | ArgsError::InvalidValue { flag, error } )
 }
 
-/// Accepts **sorted** `Vec`s of values and returns `Vec` of numbers
-/// present in both `available` and `allowed`. If `allowed` contains
-/// [`Value::Any`], all numbers are allowed.
-/// 
-/// # Examples
-/// 
-/// ```
-/// # use Value::*;
-/// assert_eq!
-/// (
-///   filter_allowed
-///   (
-///     vec![ 240, 360, 720 ],
-///     vec![ Number( 360 ), Number( 720 ) ],
-///   ),
-///   vec![ 720 ],
-/// );
-/// ```
-/// 
-/// ```
-/// # use Value::*;
-/// assert_eq!
-/// (
-///   filter_allowed
-///   (
-///     vec![ 240, 360, 720 ],
-///     vec![ Number( 360 ), Any ],
-///   ),
-///   vec![ 240, 360, 720 ],
-/// );
-/// ```
-fn filter_allowed( available : Vec< i32 >, allowed : Vec< Value > ) -> Vec< i32 >
+/// Parses command line arguments without the program name.
+fn parse_args( args : impl IntoIterator< Item = String > ) -> Result< Command, ArgsError >
 {
-  if allowed.iter().any( | x | *x == Value::Any )
+  let mut args = args.into_iter();
+  let mut available = None;
+  let mut ladder = None;
+  let mut allowed = None;
+  let mut preferred = None;
+  let mut strategy = None;
+  let mut order = None;
+  let mut fallback = None;
+  let mut explain = false;
+  let mut manifest = None;
+
+  while let Some( arg ) = args.next()
   {
-    available
+    let flag = match arg.as_str()
+    {
+      "batch" =>
+      {
+        let path = args.next().filter( | x | x != "-" );
+        return match args.next()
+        {
+          Some( extra ) => Err( ArgsError::UnknownArgument( extra ) ),
+          None => Ok( Command::Batch { path } ),
+        };
+      }
+      "demo" =>
+      {
+        let dir = args.next();
+        return match args.next()
+        {
+          Some( extra ) => Err( ArgsError::UnknownArgument( extra ) ),
+          None => Ok( Command::Demo { dir } ),
+        };
+      }
+      "hls" | "dash" =>
+      {
+        let command = if arg == "hls"
+        {
+          "hls"
+        }
+        else
+        {
+          "dash"
+        };
+        manifest = Some( ( command, args.next().ok_or( ArgsError::MissingValue( command ) )? ) );
+        continue;
+      }
+      "ladders" => return Ok( Command::Ladders ),
+      "-h" | "--help" => return Ok( Command::Help ),
+      "--explain" =>
+      {
+        explain = true;
+        continue;
+      }
+      "--available" => "--available",
+      "--ladder" => "--ladder",
+      "--allowed" => "--allowed",
+      "--preferred" => "--preferred",
+      "--strategy" => "--strategy",
+      "--order" => "--order",
+      "--fallback" => "--fallback",
+      _ => return Err( ArgsError::UnknownArgument( arg ) ),
+    };
+    let list = args.next().ok_or( ArgsError::MissingValue( flag ) )?;
+    let duplicate = match flag
+    {
+      "--available" => available.replace( parse_value::< NumberList< Resolution > >( flag, &list )?.0 ).is_some(),
+      "--ladder" => ladder.replace( list ).is_some(),
+      "--allowed" => allowed.replace( parse_value::< ValueList< Resolution > >( flag, &list )?.0 ).is_some(),
+      "--preferred" => preferred.replace( parse_value::< ValueList< Resolution > >( flag, &list )?.0 ).is_some(),
+      "--strategy" => strategy.replace( parse_value( flag, &list )? ).is_some(),
+      "--fallback" => fallback.replace( parse_value( flag, &list )? ).is_some(),
+      _ => order.replace( parse_value( flag, &list )? ).is_some(),
+    };
+    if duplicate
+    {
+      return Err( ArgsError::DuplicateArgument( flag ) );
+    }
   }
-  else
+
+  let allowed = allowed.unwrap_or( vec![ Value::Any ] );
+  let preferred = preferred.unwrap_or( vec![ Value::Any ] );
+  let options = SelectionOptions { strategy : strategy.unwrap_or_default(), order : order.unwrap_or_default() };
+
+  if let Some( ( command, path ) ) = manifest
+  {
+    let given =
+    [
+      ( available.is_some(), "--available" ),
+      ( ladder.is_some(), "--ladder" ),
+      ( fallback.is_some(), "--fallback" ),
+      ( explain, "--explain" ),
+    ];
+    if let Some( ( _, flag ) ) = given.into_iter().find( | ( given, _ ) | *given )
+    {
+      return Err( ArgsError::ConflictingArguments( command, flag ) );
+    }
+    return Ok
+    (
+      match command
+      {
+        "hls" => Command::Hls { path, allowed, preferred, options },
+        _ => Command::Dash { path, allowed, preferred, options },
+      }
+    );
+  }
+
+  let available = match ( available, ladder )
+  {
+    ( Some( _ ), Some( _ ) ) => return Err( ArgsError::ConflictingArguments( "--available", "--ladder" ) ),
+    ( Some( available ), None ) => available,
+    ( None, Some( name ) ) => match LadderCatalog::default().get( &name )
+    {
+      Some( ladder ) => ladder.heights.iter().map( | x | Resolution::from( *x ) ).collect(),
+      None => return Err( ArgsError::UnknownLadder( name ) ),
+    },
+    ( None, None ) => return Err( ArgsError::MissingArgument( "--available" ) ),
+  };
+
+  Ok
+  (
+    Command::Select
+    {
+      available,
+      allowed,
+      preferred,
+      options,
+      fallback,
+      explain,
+    }
+  )
+}
+
+/// Prints result of a single selection, or its explanation
+/// if `explain` is set. An empty result is diagnosed and
+/// replaced with the one of `fallback`, if given.
+fn select
+(
+  available : &[ Resolution ],
+  allowed : &[ Value< Resolution > ],
+  preferred : &[ Value< Resolution > ],
+  options : &SelectionOptions,
+  fallback : Option< &FallbackPolicy< Resolution > >,
+  explain : bool,
+) -> ExitCode
+{
+  let output = check_sorted( available, Argument::Available )
+  .and( check_sorted_values( allowed, Argument::Allowed ) )
+  .and( check_sorted_values( preferred, Argument::Preferred ) )
+  .and_then( | () | attempt_explained( available, allowed, preferred, options ) );
+
+  let explanation = match output
   {
-    let mut result = vec![];
-    let mut available = available.into_iter().peekable();
-    let mut allowed = allowed.into_iter().map( | x | x.assume_number() ).peekable();
+    Ok( explanation ) => explanation,
+    Err( error ) =>
+    {
+      eprintln!( "error: {}", error );
+      return ExitCode::from( 2 );
+    }
+  };
+  if explain
+  {
+    print!( "{}", explanation );
+  }
 
-    loop
+  let mut selected = explanation.selected;
+  if selected.is_empty()
+  {
+    if let Ok( Some( diagnosis ) ) = diagnose_empty( available, allowed, preferred, options )
     {
-      match ( available.peek(), allowed.peek(), )
+      eprintln!( "note: {}", diagnosis );
+    }
+    if let Some( policy ) = fallback
+    {
+      match policy.attempt( available, allowed, preferred, options )
       {
-        ( Some( &av ), Some( &al ), ) => match av.cmp( &al )
+        Ok( FallbackSelection { selected : result, level : Some( level ) } ) =>
         {
-          std::cmp::Ordering::Greater =>
-          {
-            allowed.next();
-          }
-          std::cmp::Ordering::Less =>
+          eprintln!( "note: selected by fallback {} `{}`", level + 1, policy.chain[ level ] );
+          if explain
           {
-            available.next();
+            println!( "fallback  : {}\nreturns   : {}", policy.chain[ level ], NumberList( result.clone() ) );
           }
-          std::cmp::Ordering::Equal =>
-          {
-            result.push( av );
-            available.next();
-            allowed.next();
-          }
-        },
-        _ => break,
+          selected = result;
+        }
+        Ok( _ ) => {}
+        Err( error ) =>
+        {
+          eprintln!( "error: {} in `--fallback`", error );
+          return ExitCode::from( 2 );
+        }
       }
     }
+  }
+  if !explain
+  {
+    println!( "{}", NumberList( selected.clone() ) );
+  }
 
-    result
+  if selected.is_empty()
+  {
+    ExitCode::from( 1 )
+  }
+  else
+  {
+    ExitCode::SUCCESS
   }
 }
 
-/// Accepts **sorted** `Vec`s of values and returns `Vec` of numbers
-/// present in `available` and equal or greater (or smaller if no
-/// such values are present) to those in `preferred`. If `preferred`
-/// contains [`Value::Any`], all numbers are preferred.
-/// 
-/// # Examples
-/// 
-/// ```
-/// # use Value::*;
-/// assert_eq!
-/// (
-///   filter_preferred
-///   (
-///     vec![ 240, 360, 1080 ],
-///     vec![ Number( 360 ), Number( 720 ) ],
-///   ),
-///   vec![ 360, 1080 ],
-/// );
-/// ```
-/// 
-/// ```
-/// # use Value::*;
-/// assert_eq!
-/// (
-///   filter_preferred
-///   (
-///     vec![ 240, 360, 720 ],
-///     vec![ Number( 360 ), Any ],
-///   ),
-///   vec![ 240, 360, 720 ],
-/// );
-/// ```
-fn find_preferred( available : Vec< i32 >, preferred : Vec< Value > ) -> Vec< i32 >
+/// Returns `values` with resolutions replaced by their heights,
+/// as used by manifests.
+fn heights( values : &[ Value< Resolution > ] ) -> Vec< Value< u32 > >
 {
-  if preferred.iter().any( | x | *x == Value::Any )
+  values.iter().map( | x | x.clone().map( | x | x.height ) ).collect()
+}
+
+/// Prints HLS master playlist at `path` rewritten to the variants
+/// with selected heights.
+fn hls( path : &str, allowed : &[ Value< Resolution > ], preferred : &[ Value< Resolution > ], options : &SelectionOptions ) -> ExitCode
+{
+  let playlist = match std::fs::read_to_string( path )
   {
-    available.to_vec()
-  }
-  else
+    Ok( text ) => MasterPlaylist::parse( &text ).map_err( | error | error.to_string() ),
+    Err( error ) => Err( format!( "cannot read `{}`: {}", path, error ) ),
+  };
+  let playlist = match playlist
   {
-    let mut result = vec![];
+    Ok( playlist ) => playlist,
+    Err( error ) =>
+    {
+      eprintln!( "error: {}", error );
+      return ExitCode::from( 2 );
+    }
+  };
+
+  let ( available, allowed, preferred ) = ( playlist.available(), heights( allowed ), heights( preferred ) );
+  let selected = check_sorted_values( &allowed, Argument::Allowed )
+  .and( check_sorted_values( &preferred, Argument::Preferred ) )
+  .and_then( | () | attempt_with( &available, &allowed, &preferred, options ) );
 
-    for pref in preferred.into_iter().map( | x | x.assume_number() )
+  match selected
+  {
+    Ok( selected ) if selected.is_empty() =>
     {
-      let mut index = available.partition_point( | x | *x < pref );
-      if index > 0 && available.len() == index
+      if let Ok( Some( diagnosis ) ) = diagnose_empty( &available, &allowed, &preferred, options )
       {
-        index -= 1;
+        eprintln!( "note: {}", diagnosis );
       }
-      if !available.is_empty()
+      eprintln!( "error: no variant selected" );
+      ExitCode::from( 1 )
+    }
+    Ok( selected ) =>
+    {
+      print!( "{}", playlist.rewrite( &selected ) );
+      ExitCode::SUCCESS
+    }
+    Err( error ) =>
+    {
+      eprintln!( "error: {}", error );
+      ExitCode::from( 2 )
+    }
+  }
+}
+
+/// Prints DASH manifest at `path` rewritten to the representations
+/// with selected heights of each adaptation set.
+fn dash( path : &str, allowed : &[ Value< Resolution > ], preferred : &[ Value< Resolution > ], options : &SelectionOptions ) -> ExitCode
+{
+  let mpd = match std::fs::read_to_string( path )
+  {
+    Ok( text ) => Mpd::parse( &text ).map_err( | error | error.to_string() ),
+    Err( error ) => Err( format!( "cannot read `{}`: {}", path, error ) ),
+  };
+  let mpd = match mpd
+  {
+    Ok( mpd ) => mpd,
+    Err( error ) =>
+    {
+      eprintln!( "error: {}", error );
+      return ExitCode::from( 2 );
+    }
+  };
+
+  let ( allowed, preferred ) = ( heights( allowed ), heights( preferred ) );
+
+  match mpd.selection( &allowed, &preferred, options )
+  {
+    Ok( selection ) if selection.iter().all( Vec::is_empty ) =>
+    {
+      if let Ok( Some( diagnosis ) ) = diagnose_empty( &mpd.available(), &allowed, &preferred, options )
       {
-        result.push( available[ index ] );
+        eprintln!( "note: {}", diagnosis );
       }
+      eprintln!( "error: no representation selected" );
+      ExitCode::from( 1 )
+    }
+    Ok( selection ) =>
+    {
+      print!( "{}", mpd.rewrite_sets( &selection ) );
+      ExitCode::SUCCESS
+    }
+    Err( error ) =>
+    {
+      eprintln!( "error: {}", error );
+      ExitCode::from( 2 )
     }
-
-    result.dedup();
-    result
   }
 }
 
-/// Prints out function arguments and result returned by
-/// `attempt` function with given arguments.
-fn print_attempt( available : &[ i32 ], allowed : &[ Value ], preferred : &[ Value ] )
+/// Runs requests from file at `path` or from standard input.
+fn batch( path : Option< &str > ) -> ExitCode
 {
-  print!( "available : [ " );
-  available.iter().for_each( | x | print!( "{:?}, ", x ) );
-  println!( "]" );
-  print!( "allowed   : [ " );
-  allowed.iter().for_each( | x | print!( "{:?}, ", x ) );
-  println!( "]" );
-  print!( "preferred : [ " );
-  preferred.iter().for_each( | x | print!( "{:?}, ", x ) );
-  println!( "]" );
-  let output = attempt(available, allowed, preferred);
-  print!( "returns   : [ " );
-  output.iter().for_each( | x | print!( "{:?}, ", x ) );
-  println!( "]" );
-  println!();
+  let stdout = std::io::stdout().lock();
+  let summary = match path
+  {
+    Some( path ) => std::fs::File::open( path )
+    .and_then( | file | run_batch::< i32 >( std::io::BufReader::new( file ), stdout ) ),
+    None => run_batch::< i32 >( std::io::stdin().lock(), stdout ),
+  };
+
+  match summary
+  {
+    Ok( summary ) if summary.failed == 0 => ExitCode::SUCCESS,
+    Ok( summary ) =>
+    {
+      eprintln!( "error: {} of {} requests failed", summary.failed, summary.total );
+      ExitCode::from( 1 )
+    }
+    Err( error ) =>
+    {
+      eprintln!( "error: {}", error );
+      ExitCode::from( 2 )
+    }
+  }
 }
 
-fn main()
+fn main() -> ExitCode
 {
-  use Value::*;
-
-  print_attempt
-  (
-    &[ 240, 360, 720 ],
-    &[ Number( 360 ), Number( 720 ) ],
-    &[ Number( 1080 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 720 ],
-    &[ Number( 360 ), Number( 720 ) ],
-    &[ Number( 1080 ) ],
-  );
-  print_attempt
-  (
-    &[ 240 ],
-    &[ Number( 360 ), Number( 720 ) ],
-    &[ Number( 1080 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 360, 720 ],
-    &[ Number( 240 ), Number( 360 ), Number( 720 ), Number( 1080 ) ],
-    &[ Number( 240 ), Number( 360 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 720 ],
-    &[ Number( 240 ), Number( 360 ), Number( 720 ), Number( 1080 ) ],
-    &[ Number( 240 ), Number( 360 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 720 ],
-    &[ Number( 240 ), Number( 360 ), Number( 1080 ) ],
-    &[ Number( 240 ), Number( 360 ) ],
-  );
-  print_attempt
-  (
-    &[ 720 ],
-    &[ Number( 240 ), Number( 360 ), Number( 1080 ) ],
-    &[ Number( 240 ), Number( 360 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 360 ],
-    &[ Number( 240 ), Number( 360 ) ],
-    &[ Number( 720 ), Number( 1080 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 360, 720 ],
-    &[ Number( 360 ), Any ],
-    &[ Number( 360 ), Number( 720 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 360, 720 ],
-    &[ Number( 240 ), Number( 360 ), Number( 720 ) ],
-    &[ Any, Number( 720 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 360, 720 ],
-    &[ Number( 360 ), Number( 1080 ) ],
-    &[ Any, Number( 720 ) ],
-  );
-  print_attempt
-  (
-    &[ 240, 360, 720 ],
-    &[ Number( 1080 ) ],
-    &[ Any, Number( 720 ) ],
-  );
+  match parse_args( std::env::args().skip( 1 ) )
+  {
+    Ok( Command::Select { available, allowed, preferred, options, fallback, explain } ) =>
+    {
+      select( &available, &allowed, &preferred, &options, fallback.as_ref(), explain )
+    }
+    Ok( Command::Hls { path, allowed, preferred, options } ) => hls( &path, &allowed, &preferred, &options ),
+    Ok( Command::Dash { path, allowed, preferred, options } ) => dash( &path, &allowed, &preferred, &options ),
+    Ok( Command::Batch { path } ) => batch( path.as_deref() ),
+    Ok( Command::Demo { dir } ) => demo( dir.as_deref() ),
+    Ok( Command::Ladders ) =>
+    {
+      for ladder in LadderCatalog::default().ladders()
+      {
+        println!( "{}", ladder );
+      }
+      ExitCode::SUCCESS
+    }
+    Ok( Command::Help ) =>
+    {
+      println!( "{}", USAGE );
+      ExitCode::SUCCESS
+    }
+    Err( error ) =>
+    {
+      eprintln!( "error: {}\n\n{}", error, USAGE );
+      ExitCode::from( 2 )
+    }
+  }
 }
 
 #[ cfg( test ) ]
 mod tests
 {
-  use crate::attempt;
-  use crate::Value::*;
+  use super::{ parse_args, ArgsError, Command, SCENARIOS };
+  use task_rust::{ load_scenarios, Fallback, FallbackPolicy, MatchStrategy, NumberList, ParseError, Resolution, ResultOrder, Scenario, SelectionOptions, Tie, Value, Value::* };
+
+  fn args( line : &str ) -> Vec< String >
+  {
+    line.split_whitespace().map( String::from ).collect()
+  }
 
   #[ test ]
-  fn test1()
+  fn test_select()
   {
     assert_eq!
     (
-      attempt
+      parse_args( args( "--available 240,360,720 --allowed 360,any --preferred 1080" ) ),
+      Ok
       (
-        &[ 240, 360, 720 ],
-        &[ Number( 360 ), Number( 720 ) ],
-        &[ Number( 1080 ) ],
+        Command::Select
+        {
+          available : vec![ 240.into(), 360.into(), 720.into() ],
+          allowed : vec![ Number( 360.into() ), Any ],
+          preferred : vec![ Number( 1080.into() ) ],
+          options : SelectionOptions::default(),
+          fallback : None,
+          explain : false,
+        }
       ),
-      vec![ 720 ],
     );
   }
 
   #[ test ]
-  fn test2()
+  fn test_bundled_scenarios()
   {
-    assert_eq!
-    (
-      attempt
-      (
-        &[ 240, 720 ],
-        &[ Number( 360 ), Number( 720 ) ],
-        &[ Number( 1080 ) ]
-      ),
-      vec![ 720 ],
-    );
+    let loaded = load_scenarios( concat!( env!( "CARGO_MANIFEST_DIR" ), "/fixtures/scenarios" ) ).unwrap();
+    let bundled : Vec< _ > = SCENARIOS.iter().map( | ( name, text ) | Scenario::parse( name, text ).unwrap() ).collect();
+    assert_eq!( bundled, loaded );
   }
 
   #[ test ]
-  fn test3()
+  fn test_defaults()
   {
     assert_eq!
     (
-      attempt
+      parse_args( args( "--available 240" ) ),
+      Ok
       (
-        &[ 240 ],
-        &[ Number( 360 ), Number( 720 ) ],
-        &[ Number( 1080 ) ]
+        Command::Select
+        {
+          available : vec![ 240.into() ],
+          allowed : vec![ Any ],
+          preferred : vec![ Any ],
+          options : SelectionOptions::default(),
+          fallback : None,
+          explain : false,
+        }
       ),
-      vec![],
     );
   }
 
   #[ test ]
-  fn test4()
+  fn test_errors()
   {
+    assert_eq!( parse_args( args( "" ) ), Err( ArgsError::MissingArgument( "--available" ) ) );
+    assert_eq!( parse_args( args( "--available" ) ), Err( ArgsError::MissingValue( "--available" ) ) );
+    assert_eq!( parse_args( args( "--bogus" ) ), Err( ArgsError::UnknownArgument( "--bogus".to_string() ) ) );
     assert_eq!
     (
-      attempt
+      parse_args( args( "--available 240 --available 360" ) ),
+      Err( ArgsError::DuplicateArgument( "--available" ) ),
+    );
+    assert_eq!
+    (
+      parse_args( args( "--available 240,abc" ) ),
+      Err
       (
-        &[ 240, 360, 720 ],
-        &[ Number( 240 ), Number( 360 ), Number( 720 ), Number( 1080 ) ],
-        &[ Number( 240 ), Number( 360 ) ],
+        ArgsError::InvalidValue
+        {
+          flag : "--available",
+          error : ParseError::InvalidValue { token : "abc".to_string(), offset : 4 },
+        }
       ),
-      vec![ 240, 360 ],
     );
-  }
-
-  #[ test ]
-  fn test5()
-  {
     assert_eq!
     (
-      attempt
+      parse_args( args( "--available any" ) ),
+      Err
       (
-        &[ 240, 720 ],
-        &[ Number( 240 ), Number( 360 ), Number( 720 ), Number( 1080 ) ],
-        &[ Number( 240 ), Number( 360 ) ],
+        ArgsError::InvalidValue
+        {
+          flag : "--available",
+          error : ParseError::InvalidValue { token : "any".to_string(), offset : 0 },
+        }
       ),
-      vec![ 240, 720 ],
     );
   }
 
   #[ test ]
-  fn test6()
+  fn test_batch()
+  {
+    assert_eq!( parse_args( args( "batch" ) ), Ok( Command::Batch { path : None } ) );
+    assert_eq!( parse_args( args( "batch -" ) ), Ok( Command::Batch { path : None } ) );
+    assert_eq!( parse_args( args( "batch cases.jsonl" ) ), Ok( Command::Batch { path : Some( "cases.jsonl".to_string() ) } ) );
+    assert_eq!( parse_args( args( "batch a b" ) ), Err( ArgsError::UnknownArgument( "b".to_string() ) ) );
+  }
+
+  #[ test ]
+  fn test_strategy()
   {
     assert_eq!
     (
-      attempt
+      parse_args( args( "--strategy nearest-down --available 240 --explain --order descending" ) ),
+      Ok
       (
-        &[ 240, 720 ],
-        &[ Number( 240 ), Number( 360 ), Number( 1080 ) ],
-        &[ Number( 240 ), Number( 360 ) ],
+        Command::Select
+        {
+          available : vec![ 240.into() ],
+          allowed : vec![ Any ],
+          preferred : vec![ Any ],
+          options : SelectionOptions { strategy : MatchStrategy::Nearest( Tie::Down ), order : ResultOrder::Descending },
+          fallback : None,
+          explain : true,
+        }
       ),
-      vec![ 240 ],
     );
-  }
-  
-  #[ test ]
-  fn test7()
-  {
     assert_eq!
     (
-      attempt
+      parse_args( args( "--available 240 --strategy up" ) ),
+      Err
       (
-        &[ 720 ],
-        &[ Number( 240 ), Number( 360 ), Number( 1080 ) ],
-        &[ Number( 240 ), Number( 360 ) ],
+        ArgsError::InvalidValue
+        {
+          flag : "--strategy",
+          error : ParseError::InvalidValue { token : "up".to_string(), offset : 0 },
+        }
       ),
-      vec![],
     );
   }
 
   #[ test ]
-  fn test8()
+  fn test_fallback()
   {
     assert_eq!
     (
-      attempt
+      parse_args( args( "--available 240 --fallback 360..;lowest" ) ),
+      Ok
       (
-        &[ 240, 360 ],
-        &[ Number( 240 ), Number( 360 ) ],
-        &[ Number( 720 ), Number( 1080 ) ],
+        Command::Select
+        {
+          available : vec![ 240.into() ],
+          allowed : vec![ Any ],
+          preferred : vec![ Any ],
+          options : SelectionOptions::default(),
+          fallback : Some( FallbackPolicy { chain : vec![ Fallback::Allowed( vec![ Value::range( Resolution::from( 360 ) .. ) ] ), Fallback::Lowest ] } ),
+          explain : false,
+        }
       ),
-      vec![ 360 ],
     );
-  }
-
-  // `any` tests
-  #[ test ]
-  fn test_any1()
-  {
     assert_eq!
     (
-      attempt
+      parse_args( args( "--available 240 --fallback any;x" ) ),
+      Err
       (
-        &[ 240, 360, 720 ],
-        &[ Number( 360 ), Any ],
-        &[ Number( 360 ), Number( 720 ) ],
+        ArgsError::InvalidValue
+        {
+          flag : "--fallback",
+          error : ParseError::InvalidValue { token : "x".to_string(), offset : 4 },
+        }
       ),
-      vec![ 360, 720 ],
     );
   }
 
   #[ test ]
-  fn test_any2()
+  fn test_labels()
   {
+    let Ok( Command::Select { available, allowed, .. } ) = parse_args( args( "--available 480p,1080p30,1080p60 --allowed HD..=4K" ) ) else
+    {
+      panic!( "expected selection" );
+    };
+    assert_eq!( NumberList( available ).to_string(), "480p, 1080p30, 1080p60" );
+    assert_eq!( allowed, vec![ Value::range( Resolution::from( 720 ) ..= Resolution::from( 2160 ) ) ] );
+  }
+
+  #[ test ]
+  fn test_ladder()
+  {
+    let Ok( Command::Select { available, .. } ) = parse_args( args( "--ladder vod@1" ) ) else
+    {
+      panic!( "expected selection" );
+    };
+    assert_eq!( NumberList( available ).to_string(), "240, 360, 480, 720, 1080" );
+    assert_eq!( parse_args( args( "ladders" ) ), Ok( Command::Ladders ) );
+    assert_eq!( parse_args( args( "--ladder vod@9" ) ), Err( ArgsError::UnknownLadder( "vod@9".to_string() ) ) );
     assert_eq!
     (
-      attempt
-      (
-        &[ 240, 360, 720 ],
-        &[ Number( 240 ), Number( 360 ), Number( 720 ) ],
-        &[ Any, Number( 720 ) ],
-      ),
-      vec![ 240, 360, 720 ],
+      parse_args( args( "--ladder vod --available 240" ) ),
+      Err( ArgsError::ConflictingArguments( "--available", "--ladder" ) ),
     );
   }
 
   #[ test ]
-  fn test_any3()
+  fn test_hls()
   {
     assert_eq!
     (
-      attempt
+      parse_args( args( "hls master.m3u8 --allowed <=1080" ) ),
+      Ok
       (
-        &[ 240, 360, 720 ],
-        &[ Number( 360 ), Number( 1080 ) ],
-        &[ Any, Number( 720 ) ],
+        Command::Hls
+        {
+          path : "master.m3u8".to_string(),
+          allowed : vec![ AtMost( 1080.into() ) ],
+          preferred : vec![ Any ],
+          options : SelectionOptions::default(),
+        }
       ),
-      vec![ 360 ],
+    );
+    assert_eq!( parse_args( args( "hls" ) ), Err( ArgsError::MissingValue( "hls" ) ) );
+    assert_eq!
+    (
+      parse_args( args( "hls master.m3u8 --explain" ) ),
+      Err( ArgsError::ConflictingArguments( "hls", "--explain" ) ),
     );
   }
 
   #[ test ]
-  fn test_any4()
+  fn test_dash()
   {
     assert_eq!
     (
-      attempt
+      parse_args( args( "dash manifest.mpd --preferred HD --strategy nearest" ) ),
+      Ok
       (
-        &[ 240, 360, 720 ],
-        &[ Number( 1080 ) ],
-        &[ Any, Number( 720 ) ],
+        Command::Dash
+        {
+          path : "manifest.mpd".to_string(),
+          allowed : vec![ Any ],
+          preferred : vec![ Number( "HD".parse().unwrap() ) ],
+          options : SelectionOptions { strategy : MatchStrategy::Nearest( Tie::Up ), ..Default::default() },
+        }
       ),
-      vec![],
+    );
+    assert_eq!( parse_args( args( "dash" ) ), Err( ArgsError::MissingValue( "dash" ) ) );
+    assert_eq!
+    (
+      parse_args( args( "dash manifest.mpd --ladder vod" ) ),
+      Err( ArgsError::ConflictingArguments( "dash", "--ladder" ) ),
     );
   }
 }
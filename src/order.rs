@@ -0,0 +1,99 @@
+use crate::ParseError;
+
+/// Order of values returned by [`attempt_with`](crate::attempt_with).
+/// Returned values are unique in every order.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::ResultOrder;
+/// assert_eq!( ResultOrder::Preference.apply( vec![ 720, 240, 720 ] ), vec![ 720, 240 ] );
+/// assert_eq!( ResultOrder::Ascending.apply( vec![ 720, 240, 720 ] ), vec![ 240, 720 ] );
+/// assert_eq!( ResultOrder::Descending.apply( vec![ 240, 720, 240 ] ), vec![ 720, 240 ] );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone, Copy, Default ) ]
+pub enum ResultOrder
+{
+  /// In order of the preferred values which selected them.
+  #[ default ]
+  Preference,
+  /// From the smallest value to the largest one.
+  Ascending,
+  /// From the largest value to the smallest one.
+  Descending,
+}
+
+impl ResultOrder
+{
+  /// Orders `values` and removes duplicates, keeping
+  /// the first occurrence for [`ResultOrder::Preference`].
+  pub fn apply< T : Ord >( &self, mut values : Vec< T > ) -> Vec< T >
+  {
+    match self
+    {
+      ResultOrder::Preference =>
+      {
+        let mut result = Vec::with_capacity( values.len() );
+        for value in values
+        {
+          if !result.contains( &value )
+          {
+            result.push( value );
+          }
+        }
+        result
+      }
+      ResultOrder::Ascending =>
+      {
+        values.sort();
+        values.dedup();
+        values
+      }
+      ResultOrder::Descending =>
+      {
+        values.sort_by( | a, b | b.cmp( a ) );
+        values.dedup();
+        values
+      }
+    }
+  }
+}
+
+/// Parses `preference`, `ascending` or `descending`.
+impl std::str::FromStr for ResultOrder
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    match s.trim()
+    {
+      "preference" => Ok( ResultOrder::Preference ),
+      "ascending" => Ok( ResultOrder::Ascending ),
+      "descending" => Ok( ResultOrder::Descending ),
+      token => Err( ParseError::InvalidValue { token : token.to_string(), offset : s.len() - s.trim_start().len() } ),
+    }
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::ResultOrder;
+
+  #[ test ]
+  fn test_unique()
+  {
+    let values = vec![ 300, 720, 300, 240, 720 ];
+    assert_eq!( ResultOrder::Preference.apply( values.clone() ), vec![ 300, 720, 240 ] );
+    assert_eq!( ResultOrder::Ascending.apply( values.clone() ), vec![ 240, 300, 720 ] );
+    assert_eq!( ResultOrder::Descending.apply( values ), vec![ 720, 300, 240 ] );
+  }
+
+  #[ test ]
+  fn test_parse()
+  {
+    assert_eq!( "descending".parse(), Ok( ResultOrder::Descending ) );
+    assert!( "random".parse::< ResultOrder >().is_err() );
+  }
+}
@@ -0,0 +1,328 @@
+use crate::{ Argument, MatchStrategy, SelectionError, Value };
+use crate::select::{ allows, numbers };
+
+/// Single encoding of a stream, e.g. `1280x720@60 4500kbps h264`.
+#[ derive( Debug, PartialEq, Eq, Clone, Hash ) ]
+pub struct Rendition
+{
+  pub width : u32,
+  pub height : u32,
+  /// Frames per second, rounded to an integer.
+  pub fps : u32,
+  /// Bitrate in kbit/s.
+  pub bitrate : u32,
+  pub codec : String,
+}
+
+/// Converts bitrate in bit/s, as given by manifests, into kbit/s
+/// of [`Rendition::bitrate`], rounded to the nearest integer.
+/// Returns `None` if it does not fit.
+pub( crate ) fn kbps( bandwidth : u64 ) -> Option< u32 >
+{
+  u32::try_from( bandwidth.saturating_add( 500 ) / 1000 ).ok()
+}
+
+/// Dimension of a [`Rendition`] constrained by [`RenditionValues`].
+#[ derive( Debug, PartialEq, Eq, Clone, Copy ) ]
+pub enum Dimension
+{
+  Height,
+  Fps,
+  Bitrate,
+  Codec,
+}
+
+impl Dimension
+{
+  /// Every dimension in the default priority order.
+  pub const ALL : [ Dimension; 4 ] = [ Dimension::Height, Dimension::Fps, Dimension::Bitrate, Dimension::Codec ];
+}
+
+/// Values of `allowed` or `preferred` for each dimension
+/// of a [`Rendition`], every one being `any` by default.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct RenditionValues
+{
+  pub height : Vec< Value< u32 > >,
+  pub fps : Vec< Value< u32 > >,
+  pub bitrate : Vec< Value< u32 > >,
+  pub codec : Vec< Value< String > >,
+}
+
+impl Default for RenditionValues
+{
+  fn default() -> Self
+  {
+    Self
+    {
+      height : vec![ Value::Any ],
+      fps : vec![ Value::Any ],
+      bitrate : vec![ Value::Any ],
+      codec : vec![ Value::Any ],
+    }
+  }
+}
+
+/// Selects renditions by constraining each of their dimensions.
+///
+/// A rendition is kept if every dimension is kept by `allowed`, with
+/// the semantics of [`filter_allowed`](crate::filter_allowed). Then the
+/// kept renditions are narrowed down one dimension at a time, in
+/// `priority` order, to those whose value is picked by `strategy` for
+/// some preferred value, so earlier dimensions win over later ones
+/// when preferences conflict. Codecs have no order and are only
+/// matched exactly, so if no preferred codec is present the
+/// renditions are kept as they are.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ Rendition, RenditionSelector, RenditionValues, Value::* };
+/// let rendition = | height, fps | Rendition { width : height * 16 / 9, height, fps, bitrate : 3000, codec : "h264".to_string() };
+/// let available = [ rendition( 720, 60 ), rendition( 1080, 30 ) ];
+///
+/// let selector = RenditionSelector
+/// {
+///   preferred : RenditionValues { height : vec![ Number( 1080 ) ], fps : vec![ Number( 60 ) ], ..Default::default() },
+///   ..Default::default()
+/// };
+/// assert_eq!( selector.select( &available ), Ok( vec![ &available[ 1 ] ] ) );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct RenditionSelector
+{
+  pub allowed : RenditionValues,
+  pub preferred : RenditionValues,
+  /// Dimensions missing from this list come last, in the
+  /// order of [`Dimension::ALL`].
+  pub priority : Vec< Dimension >,
+  pub strategy : MatchStrategy,
+}
+
+impl Default for RenditionSelector
+{
+  fn default() -> Self
+  {
+    Self
+    {
+      allowed : RenditionValues::default(),
+      preferred : RenditionValues::default(),
+      priority : Dimension::ALL.to_vec(),
+      strategy : MatchStrategy::default(),
+    }
+  }
+}
+
+impl RenditionSelector
+{
+  /// Returns renditions of `available` matching the selector,
+  /// in their original order.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if `preferred` contains
+  /// constraints.
+  pub fn select< 'a >( &self, available : &'a [ Rendition ] ) -> Result< Vec< &'a Rendition >, SelectionError >
+  {
+    let allowed = &self.allowed;
+    let mut selected : Vec< _ > = available
+    .iter()
+    .filter( | x | allows( &allowed.height, &x.height ) )
+    .filter( | x | allows( &allowed.fps, &x.fps ) )
+    .filter( | x | allows( &allowed.bitrate, &x.bitrate ) )
+    .filter( | x | allows( &allowed.codec, &x.codec ) )
+    .collect();
+
+    let mut priority = self.priority.clone();
+    priority.extend( Dimension::ALL.iter().filter( | x | !self.priority.contains( x ) ) );
+
+    let strategy = | values : &[ u32 ], preferred : &u32 | self.strategy.pick( values, preferred ).copied();
+    for dimension in priority
+    {
+      selected = match dimension
+      {
+        Dimension::Height => narrow( selected, &self.preferred.height, | x | x.height, strategy )?,
+        Dimension::Fps => narrow( selected, &self.preferred.fps, | x | x.fps, strategy )?,
+        Dimension::Bitrate => narrow( selected, &self.preferred.bitrate, | x | x.bitrate, strategy )?,
+        Dimension::Codec =>
+        {
+          let narrowed = narrow
+          (
+            selected.clone(),
+            &self.preferred.codec,
+            | x | x.codec.clone(),
+            | values, preferred | values.contains( preferred ).then( || preferred.clone() ),
+          )?;
+          if narrowed.is_empty()
+          {
+            selected
+          }
+          else
+          {
+            narrowed
+          }
+        }
+      };
+    }
+
+    Ok( selected )
+  }
+}
+
+/// Keeps renditions whose value of `key` is picked by `pick`
+/// from the present values for some value of `preferred`.
+fn narrow< 'a, K : Ord + Clone >
+(
+  renditions : Vec< &'a Rendition >,
+  preferred : &[ Value< K > ],
+  key : impl Fn( &Rendition ) -> K,
+  pick : impl Fn( &[ K ], &K ) -> Option< K >,
+) -> Result< Vec< &'a Rendition >, SelectionError >
+{
+  if preferred.contains( &Value::Any )
+  {
+    return Ok( renditions );
+  }
+
+  let mut values : Vec< _ > = renditions.iter().map( | x | key( x ) ).collect();
+  values.sort();
+  values.dedup();
+  let picked : Vec< _ > = numbers( preferred, Argument::Preferred )?
+  .iter()
+  .filter_map( | x | pick( &values, x ) )
+  .collect();
+
+  Ok( renditions.into_iter().filter( | x | picked.contains( &key( x ) ) ).collect() )
+}
+
+impl std::fmt::Display for Rendition
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    write!( f, "{}x{}@{} {}kbps {}", self.width, self.height, self.fps, self.bitrate, self.codec )
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ Dimension, Rendition, RenditionSelector, RenditionValues };
+  use crate::{ Argument, MatchStrategy, SelectionError, Value, Value::* };
+
+  fn rendition( height : u32, fps : u32, bitrate : u32, codec : &str ) -> Rendition
+  {
+    Rendition { width : height * 16 / 9, height, fps, bitrate, codec : codec.to_string() }
+  }
+
+  fn ladder() -> Vec< Rendition >
+  {
+    vec!
+    [
+      rendition( 360, 30, 800, "h264" ),
+      rendition( 720, 30, 2500, "h264" ),
+      rendition( 720, 60, 4500, "h264" ),
+      rendition( 1080, 30, 5000, "h264" ),
+      rendition( 1080, 30, 4000, "hevc" ),
+      rendition( 1080, 60, 8000, "h264" ),
+    ]
+  }
+
+  #[ test ]
+  fn test_allowed()
+  {
+    let ladder = ladder();
+    let selector = RenditionSelector
+    {
+      allowed : RenditionValues
+      {
+        bitrate : vec![ AtMost( 5000 ) ],
+        codec : vec![ Not( "hevc".to_string() ) ],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    assert_eq!( selector.select( &ladder ), Ok( vec![ &ladder[ 0 ], &ladder[ 1 ], &ladder[ 2 ], &ladder[ 3 ] ] ) );
+  }
+
+  #[ test ]
+  fn test_priority()
+  {
+    let ladder = ladder();
+    let mut selector = RenditionSelector
+    {
+      allowed : RenditionValues { codec : vec![ Number( "h264".to_string() ) ], ..Default::default() },
+      preferred : RenditionValues
+      {
+        height : vec![ Number( 1080 ) ],
+        bitrate : vec![ Number( 5000 ) ],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    assert_eq!( selector.select( &ladder ), Ok( vec![ &ladder[ 3 ] ] ) );
+
+    selector.preferred.fps = vec![ Number( 60 ) ];
+    assert_eq!( selector.select( &ladder ), Ok( vec![ &ladder[ 5 ] ] ) );
+
+    selector.priority = vec![ Dimension::Bitrate ];
+    selector.preferred.height = vec![ Number( 720 ) ];
+    assert_eq!( selector.select( &ladder ), Ok( vec![ &ladder[ 3 ] ] ) );
+  }
+
+  #[ test ]
+  fn test_codec()
+  {
+    let ladder = ladder();
+    let selector = RenditionSelector
+    {
+      preferred : RenditionValues { codec : vec![ Number( "av1".to_string() ) ], ..Default::default() },
+      ..Default::default()
+    };
+    assert_eq!( selector.select( &ladder ), Ok( ladder.iter().collect() ) );
+
+    let selector = RenditionSelector
+    {
+      preferred : RenditionValues
+      {
+        codec : vec![ Number( "av1".to_string() ), Number( "hevc".to_string() ) ],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    assert_eq!( selector.select( &ladder ), Ok( vec![ &ladder[ 4 ] ] ) );
+
+    let selector = RenditionSelector
+    {
+      preferred : RenditionValues
+      {
+        height : vec![ Number( 1000 ) ],
+        codec : vec![ Number( "av1".to_string() ), Number( "hevc".to_string() ) ],
+        ..Default::default()
+      },
+      strategy : MatchStrategy::RoundDown,
+      ..Default::default()
+    };
+    assert_eq!( selector.select( &ladder ), Ok( vec![ &ladder[ 1 ], &ladder[ 2 ] ] ) );
+  }
+
+  #[ test ]
+  fn test_preferred_constraint()
+  {
+    let selector = RenditionSelector
+    {
+      preferred : RenditionValues { fps : vec![ Value::range( 30 ..= 60 ) ], ..Default::default() },
+      ..Default::default()
+    };
+    assert_eq!
+    (
+      selector.select( &ladder() ),
+      Err( SelectionError::UnexpectedConstraint { argument : Argument::Preferred } ),
+    );
+  }
+
+  #[ test ]
+  fn test_display()
+  {
+    assert_eq!( rendition( 720, 60, 4500, "h264" ).to_string(), "1280x720@60 4500kbps h264" );
+  }
+}
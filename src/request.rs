@@ -0,0 +1,179 @@
+use crate::json::{ number_from_json, number_to_json, value_from_json, value_to_json, Json };
+use crate::{ attempt_checked, JsonError, SelectionError, Value };
+
+/// Arguments of a single [`attempt`](crate::attempt) call, exchanged
+/// as JSON: `{"available":[240,720],"allowed":["any"],"preferred":[1080]}`.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ SelectionRequest, SelectionResponse, Value::* };
+/// let request = SelectionRequest::< i32 >::from_json
+/// (
+///   r#"{ "available" : [ 240, 360, 720 ], "allowed" : [ 360, "any" ], "preferred" : [ 1080 ] }"#
+/// ).unwrap();
+/// assert_eq!( request.allowed, vec![ Number( 360 ), Any ] );
+/// assert_eq!( request.run(), Ok( SelectionResponse { selected : vec![ 720 ] } ) );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct SelectionRequest< T = i32 >
+{
+  pub available : Vec< T >,
+  pub allowed : Vec< Value< T > >,
+  pub preferred : Vec< Value< T > >,
+}
+
+/// Result of a [`SelectionRequest`], exchanged as JSON: `{"selected":[720]}`.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct SelectionResponse< T = i32 >
+{
+  pub selected : Vec< T >,
+}
+
+impl< T : Ord + Clone > SelectionRequest< T >
+{
+  /// Runs [`attempt_checked`] with arguments of the request.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if the selection fails.
+  pub fn run( &self ) -> Result< SelectionResponse< T >, SelectionError >
+  {
+    attempt_checked( &self.available, &self.allowed, &self.preferred ).map( | selected | SelectionResponse { selected } )
+  }
+}
+
+impl< T : std::fmt::Display > SelectionRequest< T >
+{
+  /// Returns JSON representation of the request.
+  pub fn to_json( &self ) -> String
+  {
+    self.to_json_tree().to_string()
+  }
+
+  pub( crate ) fn to_json_tree( &self ) -> Json
+  {
+    Json::Object
+    (
+      vec!
+      [
+        ( "available".to_string(), Json::Array( self.available.iter().map( number_to_json ).collect() ) ),
+        ( "allowed".to_string(), Json::Array( self.allowed.iter().map( value_to_json ).collect() ) ),
+        ( "preferred".to_string(), Json::Array( self.preferred.iter().map( value_to_json ).collect() ) ),
+      ]
+    )
+  }
+}
+
+impl< T : std::str::FromStr > SelectionRequest< T >
+{
+  /// Reads request from its JSON representation.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JsonError`] if `json` is malformed or
+  /// a field is missing or has a wrong type.
+  pub fn from_json( json : &str ) -> Result< Self, JsonError >
+  {
+    Self::from_json_tree( &Json::parse( json )?, "$" )
+  }
+
+  pub( crate ) fn from_json_tree( json : &Json, path : &str ) -> Result< Self, JsonError >
+  {
+    Ok
+    (
+      Self
+      {
+        available : json.array_field( "available", path, number_from_json )?,
+        allowed : json.array_field( "allowed", path, value_from_json )?,
+        preferred : json.array_field( "preferred", path, value_from_json )?,
+      }
+    )
+  }
+}
+
+impl< T : std::fmt::Display > SelectionResponse< T >
+{
+  /// Returns JSON representation of the response.
+  pub fn to_json( &self ) -> String
+  {
+    self.to_json_tree().to_string()
+  }
+
+  pub( crate ) fn to_json_tree( &self ) -> Json
+  {
+    Json::Object
+    (
+      vec![ ( "selected".to_string(), Json::Array( self.selected.iter().map( number_to_json ).collect() ) ) ]
+    )
+  }
+}
+
+impl< T : std::str::FromStr > SelectionResponse< T >
+{
+  /// Reads response from its JSON representation.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JsonError`] if `json` is malformed or
+  /// a field is missing or has a wrong type.
+  pub fn from_json( json : &str ) -> Result< Self, JsonError >
+  {
+    let json = Json::parse( json )?;
+    Ok( Self { selected : json.array_field( "selected", "$", number_from_json )? } )
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ SelectionRequest, SelectionResponse };
+  use crate::{ JsonError, Value::* };
+
+  #[ test ]
+  fn test_request_round_trip()
+  {
+    let request = SelectionRequest
+    {
+      available : vec![ 240_u64, 720 ],
+      allowed : vec![ Number( 240 ), Any ],
+      preferred : vec![ Number( 1080 ) ],
+    };
+    let json = request.to_json();
+    assert_eq!( json, r#"{"available":[240,720],"allowed":[240,"any"],"preferred":[1080]}"# );
+    assert_eq!( SelectionRequest::from_json( &json ), Ok( request ) );
+  }
+
+  #[ test ]
+  fn test_response_round_trip()
+  {
+    let response = SelectionResponse { selected : vec![ 360, 720 ] };
+    assert_eq!( response.to_json(), r#"{"selected":[360,720]}"# );
+    assert_eq!( SelectionResponse::from_json( &response.to_json() ), Ok( response ) );
+  }
+
+  #[ test ]
+  fn test_request_errors()
+  {
+    assert_eq!
+    (
+      SelectionRequest::< i32 >::from_json( r#"{ "available" : [ 240 ], "allowed" : [ "any" ] }"# ),
+      Err( JsonError::MissingField { path : "$.preferred".to_string() } ),
+    );
+    assert_eq!
+    (
+      SelectionRequest::< i32 >::from_json( r#"{ "available" : [ 240 ], "allowed" : [ "all" ], "preferred" : [] }"# ),
+      Err( JsonError::InvalidValue { path : "$.allowed[0]".to_string() } ),
+    );
+    assert_eq!
+    (
+      SelectionRequest::< u16 >::from_json( r#"{ "available" : [ 240, 70000 ], "allowed" : [], "preferred" : [] }"# ),
+      Err( JsonError::InvalidValue { path : "$.available[1]".to_string() } ),
+    );
+    assert_eq!
+    (
+      SelectionRequest::< i32 >::from_json( "[]" ),
+      Err( JsonError::InvalidValue { path : "$".to_string() } ),
+    );
+  }
+}
@@ -0,0 +1,248 @@
+use crate::{ Distance, ParseError };
+
+/// Named resolutions and their heights.
+const NAMES : [ ( &str, u32 ); 6 ] =
+[
+  ( "SD", 480 ),
+  ( "HD", 720 ),
+  ( "FHD", 1080 ),
+  ( "UHD", 2160 ),
+  ( "4K", 2160 ),
+  ( "8K", 4320 ),
+];
+
+/// Video resolution identified by its height, parsed from and
+/// written as a plain number (`720`), a progressive label with
+/// optional frame rate (`720p`, `1080p60`), a name (`SD`, `HD`,
+/// `FHD`, `UHD`, `4K`, `8K`) or a size (`1920x1080`).
+///
+/// Heights are compared first and frame rates next, but only if both
+/// resolutions have one, so a resolution without frame rate stands for
+/// every frame rate of its height. So `FHD`, `1920x1080` and `1080p60`
+/// are equal and `<=FHD` includes `1080p60`, while `1080p30` and
+/// `1080p60` are not equal. Each of them is written back as it was
+/// parsed. Being a number type of its own, it is accepted wherever
+/// numbers are, e.g. in [`Value`](crate::Value) and its lists.
+///
+/// A sorted list therefore cannot have values of the same height both
+/// with and without frame rate, e.g. `1080p, 1080p60`, as they are
+/// duplicates.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt, NumberList, Resolution, ValueList };
+/// let available : NumberList< Resolution > = "480p, 720p, 1080p60, 4K".parse().unwrap();
+/// let allowed : ValueList< Resolution > = "..=FHD".parse().unwrap();
+/// let preferred : ValueList< Resolution > = "1920x1080".parse().unwrap();
+///
+/// let selected = attempt( &available.0, &allowed.0, &preferred.0 ).unwrap();
+/// assert_eq!( NumberList( selected ).to_string(), "1080p60" );
+/// ```
+#[ derive( Debug, Clone, Copy ) ]
+pub struct Resolution
+{
+  pub height : u32,
+  /// Frame rate of a progressive label like `1080p60`.
+  pub fps : Option< u32 >,
+  label : Label,
+}
+
+/// How a [`Resolution`] is written.
+#[ derive( Debug, Clone, Copy ) ]
+enum Label
+{
+  Number,
+  Progressive,
+  Name( &'static str ),
+  Size
+  {
+    width : u32,
+  },
+}
+
+/// Creates resolution written as a plain number.
+impl From< u32 > for Resolution
+{
+  fn from( height : u32 ) -> Self
+  {
+    Self { height, fps : None, label : Label::Number }
+  }
+}
+
+impl PartialEq for Resolution
+{
+  fn eq( &self, other : &Self ) -> bool
+  {
+    self.cmp( other ) == std::cmp::Ordering::Equal
+  }
+}
+
+impl Eq for Resolution {}
+
+impl PartialOrd for Resolution
+{
+  fn partial_cmp( &self, other : &Self ) -> Option< std::cmp::Ordering >
+  {
+    Some( self.cmp( other ) )
+  }
+}
+
+impl Ord for Resolution
+{
+  fn cmp( &self, other : &Self ) -> std::cmp::Ordering
+  {
+    match ( self.fps, other.fps )
+    {
+      ( Some( fps ), Some( other_fps ) ) => ( self.height, fps ).cmp( &( other.height, other_fps ) ),
+      _ => self.height.cmp( &other.height ),
+    }
+  }
+}
+
+/// Compares differences of heights first and of frame rates next,
+/// the latter being 0 unless both resolutions have a frame rate.
+impl Distance for Resolution
+{
+  type Output = ( u32, u32 );
+
+  fn distance( &self, other : &Self ) -> ( u32, u32 )
+  {
+    let fps = match ( self.fps, other.fps )
+    {
+      ( Some( fps ), Some( other_fps ) ) => fps.abs_diff( other_fps ),
+      _ => 0,
+    };
+    ( self.height.abs_diff( other.height ), fps )
+  }
+}
+
+/// Parses labels case-insensitively.
+impl std::str::FromStr for Resolution
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    let token = s.trim();
+    let invalid = || ParseError::InvalidValue { token : token.to_string(), offset : s.len() - s.trim_start().len() };
+    let number = | text : &str | text.parse::< u32 >().map_err( | _ | invalid() );
+    let upper = token.to_ascii_uppercase();
+
+    if let Ok( height ) = token.parse::< u32 >()
+    {
+      return Ok( Self::from( height ) );
+    }
+    if let Some( &( name, height ) ) = NAMES.iter().find( | ( name, _ ) | *name == upper )
+    {
+      return Ok( Self { height, fps : None, label : Label::Name( name ) } );
+    }
+    if let Some( ( width, height ) ) = upper.split_once( 'X' )
+    {
+      return Ok( Self { height : number( height )?, fps : None, label : Label::Size { width : number( width )? } } );
+    }
+    if let Some( ( height, fps ) ) = upper.split_once( 'P' )
+    {
+      let fps = if fps.is_empty()
+      {
+        None
+      }
+      else
+      {
+        Some( number( fps )? )
+      };
+      return Ok( Self { height : number( height )?, fps, label : Label::Progressive } );
+    }
+    Err( invalid() )
+  }
+}
+
+impl std::fmt::Display for Resolution
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self.label
+    {
+      Label::Number => write!( f, "{}", self.height ),
+      Label::Progressive => match self.fps
+      {
+        Some( fps ) => write!( f, "{}p{}", self.height, fps ),
+        None => write!( f, "{}p", self.height ),
+      },
+      Label::Name( name ) => write!( f, "{}", name ),
+      Label::Size { width } => write!( f, "{}x{}", width, self.height ),
+    }
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::Resolution;
+  use crate::{ attempt, check_sorted, Argument, NumberList, ParseError, SelectionError, Value, ValueList };
+
+  #[ test ]
+  fn test_parse()
+  {
+    let heights = | list : &str | list.parse::< NumberList< Resolution > >().unwrap().0.iter().map( | x | x.height ).collect::< Vec< _ > >();
+    assert_eq!( heights( "360, 720p, 1080P60, 1280x720" ), vec![ 360, 720, 1080, 720 ] );
+    assert_eq!( heights( "sd, HD, FHD, UHD, 4k, 8K" ), vec![ 480, 720, 1080, 2160, 2160, 4320 ] );
+    assert_eq!( "1080p60".parse::< Resolution >().unwrap().fps, Some( 60 ) );
+    assert_eq!
+    (
+      " 720i".parse::< Resolution >(),
+      Err( ParseError::InvalidValue { token : "720i".to_string(), offset : 1 } ),
+    );
+    assert_eq!
+    (
+      "720p, 1080px".parse::< NumberList< Resolution > >(),
+      Err( ParseError::InvalidValue { token : "1080px".to_string(), offset : 6 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_display()
+  {
+    let list : ValueList< Resolution > = "240, 720p, 1080p60, 1920x1080, fhd, HD..=4k, >=8K, !sd, any".parse().unwrap();
+    assert_eq!( list.to_string(), "240, 720p, 1080p60, 1920x1080, FHD, HD..=4K, >=8K, !SD, any" );
+  }
+
+  #[ test ]
+  fn test_equality()
+  {
+    let fhd : Resolution = "FHD".parse().unwrap();
+    assert_eq!( fhd, "1920x1080".parse().unwrap() );
+    assert_eq!( fhd, Resolution::from( 1080 ) );
+    assert!( Value::range( "HD".parse::< Resolution >().unwrap() .. ).matches( &fhd ) );
+  }
+
+  #[ test ]
+  fn test_frame_rates()
+  {
+    let list = | list : &str | list.parse::< NumberList< Resolution > >().unwrap().0;
+    let values = | list : &str | list.parse::< ValueList< Resolution > >().unwrap().0;
+    assert_eq!( check_sorted( &list( "720p30, 720p60, 1080p" ), Argument::Available ), Ok( () ) );
+    assert_eq!
+    (
+      check_sorted( &list( "720p, 720p30" ), Argument::Available ),
+      Err( SelectionError::Unsorted { argument : Argument::Available, index : 1 } ),
+    );
+    assert_eq!
+    (
+      check_sorted( &list( "720p60, 720p30" ), Argument::Available ),
+      Err( SelectionError::Unsorted { argument : Argument::Available, index : 1 } ),
+    );
+    assert_ne!( "1080p30".parse::< Resolution >().unwrap(), "1080p60".parse().unwrap() );
+    assert_eq!( "FHD".parse::< Resolution >().unwrap(), "1080p60".parse().unwrap() );
+
+    let available = list( "720p30, 720p60, 1080p30, 1080p60" );
+    let selected = attempt( &available, &values( "1080p60" ), &values( "any" ) ).unwrap();
+    assert_eq!( NumberList( selected ).to_string(), "1080p60" );
+    let selected = attempt( &available, &values( "1080p" ), &values( "any" ) ).unwrap();
+    assert_eq!( NumberList( selected ).to_string(), "1080p30, 1080p60" );
+    let selected = attempt( &available, &values( "..=FHD" ), &values( "FHD" ) ).unwrap();
+    assert_eq!( NumberList( selected ).to_string(), "1080p30" );
+    let selected = attempt( &available, &values( "..=FHD" ), &values( "1080p60" ) ).unwrap();
+    assert_eq!( NumberList( selected ).to_string(), "1080p60" );
+  }
+}
@@ -0,0 +1,267 @@
+use crate::{ attempt_checked, NumberList, ParseError, ScenarioError, SelectionError, Value, ValueList };
+
+/// Arguments of [`attempt`](crate::attempt) together with the
+/// expected result, stored as text:
+///
+/// ```text
+/// available : 240, 360, 720
+/// allowed   : 360, any
+/// preferred : 1080
+/// returns   : 720
+/// ```
+///
+/// Lines starting with `#` and blank lines are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::Scenario;
+/// let scenario = Scenario::parse
+/// (
+///   "example",
+///   "available : 240, 720\nallowed : any\npreferred : 1080\nreturns : 720\n",
+/// ).unwrap();
+/// assert!( scenario.check().is_ok() );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct Scenario
+{
+  pub name : String,
+  pub available : Vec< i32 >,
+  pub allowed : Vec< Value >,
+  pub preferred : Vec< Value >,
+  pub returns : Vec< i32 >,
+}
+
+/// Scenario whose actual result differs from the expected one.
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct ScenarioMismatch< 'a >
+{
+  pub scenario : &'a Scenario,
+  pub actual : Result< Vec< i32 >, SelectionError >,
+}
+
+impl Scenario
+{
+  /// Parses scenario called `name` from `text`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ScenarioError`] if a line is malformed or
+  /// one of the keys is missing.
+  pub fn parse( name : &str, text : &str ) -> Result< Self, ScenarioError >
+  {
+    let syntax = | line : usize, message : String | ScenarioError::Syntax { name : name.to_string(), line, message };
+    let list = | line : usize, list : &str | -> Result< ValueList, ScenarioError >
+    {
+      list.parse().map_err( | error : ParseError | syntax( line, error.to_string() ) )
+    };
+    let numbers = | line : usize, list : &str | -> Result< NumberList, ScenarioError >
+    {
+      list.parse().map_err( | error : ParseError | syntax( line, error.to_string() ) )
+    };
+
+    let mut available = None;
+    let mut allowed = None;
+    let mut preferred = None;
+    let mut returns = None;
+
+    for ( index, line ) in text.lines().enumerate()
+    {
+      let number = index + 1;
+      let line = line.trim();
+      if line.is_empty() || line.starts_with( '#' )
+      {
+        continue;
+      }
+
+      let ( key, value ) = line.split_once( ':' ).ok_or_else( || syntax( number, "expected `key : list`".to_string() ) )?;
+      let duplicate = match key.trim()
+      {
+        "available" => available.replace( numbers( number, value )?.0 ).is_some(),
+        "allowed" => allowed.replace( list( number, value )?.0 ).is_some(),
+        "preferred" => preferred.replace( list( number, value )?.0 ).is_some(),
+        "returns" => returns.replace( numbers( number, value )?.0 ).is_some(),
+        key => return Err( syntax( number, format!( "unknown key `{}`", key ) ) ),
+      };
+      if duplicate
+      {
+        return Err( syntax( number, format!( "duplicate key `{}`", key.trim() ) ) );
+      }
+    }
+
+    let missing = | key | ScenarioError::MissingKey { name : name.to_string(), key };
+    Ok
+    (
+      Self
+      {
+        name : name.to_string(),
+        available : available.ok_or_else( || missing( "available" ) )?,
+        allowed : allowed.ok_or_else( || missing( "allowed" ) )?,
+        preferred : preferred.ok_or_else( || missing( "preferred" ) )?,
+        returns : returns.ok_or_else( || missing( "returns" ) )?,
+      }
+    )
+  }
+
+  /// Runs [`attempt_checked`] with arguments of the scenario.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if the selection fails.
+  pub fn run( &self ) -> Result< Vec< i32 >, SelectionError >
+  {
+    attempt_checked( &self.available, &self.allowed, &self.preferred )
+  }
+
+  /// Runs the scenario and compares its result with the expected one.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ScenarioMismatch`] if the results differ.
+  pub fn check( &self ) -> Result< (), ScenarioMismatch< '_ > >
+  {
+    match self.run()
+    {
+      Ok( actual ) if actual == self.returns => Ok( () ),
+      actual => Err( ScenarioMismatch { scenario : self, actual } ),
+    }
+  }
+
+  /// Writes the argument lines shared by scenario and mismatch output.
+  fn fmt_arguments( &self, f : &mut std::fmt::Formatter< '_ >, indent : &str ) -> std::fmt::Result
+  {
+    writeln!( f, "{}available : {}", indent, NumberList( self.available.clone() ) )?;
+    writeln!( f, "{}allowed   : {}", indent, ValueList( self.allowed.clone() ) )?;
+    writeln!( f, "{}preferred : {}", indent, ValueList( self.preferred.clone() ) )
+  }
+}
+
+impl std::fmt::Display for Scenario
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    self.fmt_arguments( f, "" )?;
+    writeln!( f, "returns   : {}", NumberList( self.returns.clone() ) )
+  }
+}
+
+/// Shows the scenario with a diff of expected (`-`)
+/// and actual (`+`) results.
+impl std::fmt::Display for ScenarioMismatch< '_ >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    writeln!( f, "scenario `{}` failed", self.scenario.name )?;
+    self.scenario.fmt_arguments( f, "  " )?;
+    writeln!( f, "- returns   : {}", NumberList( self.scenario.returns.clone() ) )?;
+    match &self.actual
+    {
+      Ok( actual ) => writeln!( f, "+ returns   : {}", NumberList( actual.clone() ) ),
+      Err( error ) => writeln!( f, "+ error     : {}", error ),
+    }
+  }
+}
+
+/// Loads every `*.txt` scenario in directory `dir`, ordered by
+/// file name. The name of a scenario is its file name without
+/// extension.
+///
+/// # Errors
+///
+/// Returns [`ScenarioError`] if the directory or a file cannot
+/// be read or parsed.
+pub fn load_scenarios( dir : impl AsRef< std::path::Path > ) -> Result< Vec< Scenario >, ScenarioError >
+{
+  let io = | path : &std::path::Path, error : std::io::Error | ScenarioError::Io
+  {
+    path : path.display().to_string(),
+    message : error.to_string(),
+  };
+  let dir = dir.as_ref();
+
+  let mut paths = std::fs::read_dir( dir )
+  .map_err( | error | io( dir, error ) )?
+  .map( | entry | entry.map( | entry | entry.path() ).map_err( | error | io( dir, error ) ) )
+  .collect::< Result< Vec< _ >, _ > >()?;
+  paths.retain( | path | path.extension().is_some_and( | x | x == "txt" ) );
+  paths.sort();
+
+  paths
+  .iter()
+  .map( | path |
+  {
+    let text = std::fs::read_to_string( path ).map_err( | error | io( path, error ) )?;
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    Scenario::parse( &name, &text )
+  })
+  .collect()
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ load_scenarios, Scenario };
+  use crate::{ ScenarioError, Value::* };
+
+  #[ test ]
+  fn test_fixtures()
+  {
+    let scenarios = load_scenarios( concat!( env!( "CARGO_MANIFEST_DIR" ), "/fixtures/scenarios" ) ).unwrap();
+    assert!( !scenarios.is_empty() );
+
+    let mismatches : Vec< _ > = scenarios.iter().filter_map( | x | x.check().err() ).map( | x | x.to_string() ).collect();
+    assert!( mismatches.is_empty(), "\n{}", mismatches.join( "\n" ) );
+  }
+
+  #[ test ]
+  fn test_parse()
+  {
+    assert_eq!
+    (
+      Scenario::parse( "a", "# comment\n\navailable : 240\nallowed : any\npreferred : [ ]\nreturns :\n" ),
+      Ok
+      (
+        Scenario
+        {
+          name : "a".to_string(),
+          available : vec![ 240 ],
+          allowed : vec![ Any ],
+          preferred : vec![],
+          returns : vec![],
+        }
+      ),
+    );
+  }
+
+  #[ test ]
+  fn test_parse_errors()
+  {
+    assert_eq!
+    (
+      Scenario::parse( "a", "available : 240\nbest : 1\n" ),
+      Err( ScenarioError::Syntax { name : "a".to_string(), line : 2, message : "unknown key `best`".to_string() } ),
+    );
+    assert_eq!
+    (
+      Scenario::parse( "a", "available : 240, x\n" ),
+      Err( ScenarioError::Syntax { name : "a".to_string(), line : 1, message : "invalid value `x` at offset 6".to_string() } ),
+    );
+    assert_eq!
+    (
+      Scenario::parse( "a", "available : 240\nallowed : any\npreferred : any\n" ),
+      Err( ScenarioError::MissingKey { name : "a".to_string(), key : "returns" } ),
+    );
+  }
+
+  #[ test ]
+  fn test_mismatch_diff()
+  {
+    let scenario = Scenario::parse( "b", "available : 240, 720\nallowed : 720\npreferred : 240\nreturns : 240\n" ).unwrap();
+    assert_eq!
+    (
+      scenario.check().unwrap_err().to_string(),
+      "scenario `b` failed\n  available : 240, 720\n  allowed   : 720\n  preferred : 240\n- returns   : 240\n+ returns   : 720\n",
+    );
+  }
+}
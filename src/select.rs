@@ -0,0 +1,657 @@
+use crate::{ Argument, Distance, MatchStrategy, ResultOrder, SelectionError, Value };
+use crate::{ check_sorted, check_sorted_values, SortedSet, SortedValues };
+use crate::strategy::neighbours;
+
+/// Accepts **sorted** slices of values and returns
+/// vector of a numbers in `available` slice that
+/// present in `allowed` and closest to `preferred`.
+///
+/// Works with any totally ordered type, not only `i32`.
+///
+/// # Errors
+///
+/// Returns [`SelectionError`] if any of the stages fails.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt, Value::* };
+/// assert_eq!
+/// (
+///   attempt
+///   (
+///     &[ 240, 360, 720 ],
+///     &[ Number( 360 ), Number( 720 ) ],
+///     &[ Number( 1080 ) ],
+///   ),
+///   Ok( vec![ 720 ] ),
+/// );
+/// ```
+///
+/// ```
+/// # use task_rust::{ attempt, Value::* };
+/// assert_eq!
+/// (
+///   attempt
+///   (
+///     &[ 800_u64, 1_500, 4_500 ],
+///     &[ Any ],
+///     &[ Number( 2_000 ) ],
+///   ),
+///   Ok( vec![ 4_500 ] ),
+/// );
+/// ```
+pub fn attempt< T : Ord + Clone >
+(
+  available : &[ T ],
+  allowed : &[ Value< T > ],
+  preferred : &[ Value< T > ],
+) -> Result< Vec< T >, SelectionError >
+{
+  find_preferred( filter_allowed( available.to_vec(), allowed.to_vec() )?, preferred.to_vec() )
+}
+
+/// Options of [`attempt_with`] changing how values are selected.
+#[ derive( Debug, PartialEq, Eq, Clone, Copy, Default ) ]
+pub struct SelectionOptions
+{
+  /// How preferred values are matched, see [`find_preferred_with`].
+  pub strategy : MatchStrategy,
+  /// Order of returned values.
+  pub order : ResultOrder,
+}
+
+/// Same as [`attempt`] but selects according to `options`.
+///
+/// # Errors
+///
+/// Returns [`SelectionError`] if any of the stages fails.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt_with, MatchStrategy, SelectionOptions, Value::* };
+/// assert_eq!
+/// (
+///   attempt_with
+///   (
+///     &[ 240, 360, 720 ],
+///     &[ Any ],
+///     &[ Number( 480 ) ],
+///     &SelectionOptions { strategy : MatchStrategy::RoundDown, ..Default::default() },
+///   ),
+///   Ok( vec![ 360 ] ),
+/// );
+/// ```
+///
+/// ```
+/// # use task_rust::{ attempt_with, ResultOrder, SelectionOptions, Value::* };
+/// assert_eq!
+/// (
+///   attempt_with
+///   (
+///     &[ 300, 720 ],
+///     &[ Any ],
+///     &[ Number( 240 ), Number( 1080 ), Number( 300 ) ],
+///     &SelectionOptions { order : ResultOrder::Descending, ..Default::default() },
+///   ),
+///   Ok( vec![ 720, 300 ] ),
+/// );
+/// ```
+pub fn attempt_with< T : Ord + Clone + Distance >
+(
+  available : &[ T ],
+  allowed : &[ Value< T > ],
+  preferred : &[ Value< T > ],
+  options : &SelectionOptions,
+) -> Result< Vec< T >, SelectionError >
+{
+  let result = find_preferred_with( filter_allowed( available.to_vec(), allowed.to_vec() )?, preferred.to_vec(), options.strategy )?;
+  Ok( options.order.apply( result ) )
+}
+
+/// Same as [`attempt`] but checks that every argument is sorted
+/// first instead of returning a wrong result for unsorted input.
+///
+/// # Errors
+///
+/// Returns [`SelectionError::Unsorted`] naming the first unsorted
+/// argument and the index of the offending value.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt_checked, Argument, SelectionError, Value::* };
+/// assert_eq!
+/// (
+///   attempt_checked
+///   (
+///     &[ 240, 720, 360 ],
+///     &[ Any ],
+///     &[ Number( 1080 ) ],
+///   ),
+///   Err( SelectionError::Unsorted { argument : Argument::Available, index : 2 } ),
+/// );
+/// ```
+pub fn attempt_checked< T : Ord + Clone >
+(
+  available : &[ T ],
+  allowed : &[ Value< T > ],
+  preferred : &[ Value< T > ],
+) -> Result< Vec< T >, SelectionError >
+{
+  check_sorted( available, Argument::Available )?;
+  check_sorted_values( allowed, Argument::Allowed )?;
+  check_sorted_values( preferred, Argument::Preferred )?;
+  attempt( available, allowed, preferred )
+}
+
+/// Same as [`attempt`] but accepts slices in any order,
+/// sorting them and removing duplicates first.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt_normalized, Value::* };
+/// assert_eq!
+/// (
+///   attempt_normalized
+///   (
+///     &[ 720, 240, 360, 240 ],
+///     &[ Number( 720 ), Number( 360 ) ],
+///     &[ Number( 1080 ), Number( 240 ) ],
+///   ),
+///   Ok( vec![ 360, 720 ] ),
+/// );
+/// ```
+pub fn attempt_normalized< T : Ord + Clone >
+(
+  available : &[ T ],
+  allowed : &[ Value< T > ],
+  preferred : &[ Value< T > ],
+) -> Result< Vec< T >, SelectionError >
+{
+  attempt_sorted
+  (
+    &SortedSet::new( available.to_vec() ),
+    &SortedValues::new( allowed.to_vec() ),
+    &SortedValues::new( preferred.to_vec() ),
+  )
+}
+
+/// Same as [`attempt`] but only accepts arguments which are
+/// sorted by construction, so no precondition can be violated.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ attempt_sorted, SortedSet, SortedValues, Value::* };
+/// assert_eq!
+/// (
+///   attempt_sorted
+///   (
+///     &SortedSet::new( vec![ 720, 240, 360 ] ),
+///     &SortedValues::new( vec![ Number( 720 ), Number( 360 ) ] ),
+///     &SortedValues::any(),
+///   ),
+///   Ok( vec![ 360, 720 ] ),
+/// );
+/// ```
+pub fn attempt_sorted< T : Ord + Clone >
+(
+  available : &SortedSet< T >,
+  allowed : &SortedValues< T >,
+  preferred : &SortedValues< T >,
+) -> Result< Vec< T >, SelectionError >
+{
+  attempt( available, allowed, preferred )
+}
+
+/// Accepts **sorted** `Vec`s of values and returns `Vec` of numbers
+/// present in both `available` and `allowed`. If `allowed` contains
+/// [`Value::Any`], all numbers are allowed. Numbers satisfying any of
+/// constraints of `allowed`, such as [`Value::Range`] or
+/// [`Value::AtLeast`], are allowed as well.
+///
+/// Numbers excluded by [`Value::Not`] are never allowed, even together
+/// with [`Value::Any`]. If `allowed` contains only exclusions, every
+/// other number is allowed.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ filter_allowed, Value::* };
+/// assert_eq!
+/// (
+///   filter_allowed
+///   (
+///     vec![ 240, 360, 720 ],
+///     vec![ Number( 360 ), Number( 720 ) ],
+///   ),
+///   Ok( vec![ 360, 720 ] ),
+/// );
+/// ```
+///
+/// ```
+/// # use task_rust::{ filter_allowed, Value::* };
+/// assert_eq!
+/// (
+///   filter_allowed
+///   (
+///     vec![ 240, 360, 720 ],
+///     vec![ Number( 360 ), Any ],
+///   ),
+///   Ok( vec![ 240, 360, 720 ] ),
+/// );
+/// ```
+///
+/// ```
+/// # use task_rust::{ filter_allowed, Value, Value::* };
+/// assert_eq!
+/// (
+///   filter_allowed
+///   (
+///     vec![ 240, 360, 480, 720, 1080, 1440 ],
+///     vec![ Number( 240 ), Value::range( 360 ..= 720 ), Value::range( 600 .. 1440 ) ],
+///   ),
+///   Ok( vec![ 240, 360, 480, 720, 1080 ] ),
+/// );
+/// ```
+///
+/// ```
+/// # use task_rust::{ filter_allowed, Value::* };
+/// assert_eq!
+/// (
+///   filter_allowed
+///   (
+///     vec![ 240, 360, 480, 720, 1080 ],
+///     vec![ Any, Not( 480 ) ],
+///   ),
+///   Ok( vec![ 240, 360, 720, 1080 ] ),
+/// );
+/// ```
+pub fn filter_allowed< T : Ord + Clone >( available : Vec< T >, allowed : Vec< Value< T > > ) -> Result< Vec< T >, SelectionError >
+{
+  Ok( available.into_iter().filter( | x | allows( &allowed, x ) ).collect() )
+}
+
+/// Returns `true` if `allowed` keeps `value`, see [`allowed_by`].
+pub( crate ) fn allows< T : Ord >( allowed : &[ Value< T > ], value : &T ) -> bool
+{
+  allowed_by( allowed, value ).is_ok()
+}
+
+/// Decides whether `allowed` keeps `value` with the rules of
+/// [`filter_allowed`], without requiring sorted values. Returns
+/// `Ok` with the first allowed value satisfied by `value`, or with
+/// `None` if `allowed` consists only of exclusions, and `Err` with
+/// the exclusion dropping `value`, or with `None` if no allowed
+/// value is satisfied.
+pub( crate ) fn allowed_by< 'a, T : Ord >
+(
+  allowed : &'a [ Value< T > ],
+  value : &T,
+) -> Result< Option< &'a Value< T > >, Option< &'a Value< T > > >
+{
+  let exclusion = | x : &&Value< T > | matches!( x, Value::Not( _ ) );
+  if let Some( by ) = allowed.iter().filter( exclusion ).find( | x | !x.matches( value ) )
+  {
+    return Err( Some( by ) );
+  }
+
+  let mut inclusions = allowed.iter().filter( | x | !exclusion( x ) ).peekable();
+  if inclusions.peek().is_none() && !allowed.is_empty()
+  {
+    return Ok( None );
+  }
+  inclusions.find( | x | x.matches( value ) ).map( Some ).ok_or( None )
+}
+
+/// Accepts **sorted** `Vec`s of values and returns `Vec` of numbers
+/// present in `available` and equal or greater (or smaller if no
+/// such values are present) to those in `preferred`. If `preferred`
+/// contains [`Value::Any`], all numbers are preferred.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ find_preferred, Value::* };
+/// assert_eq!
+/// (
+///   find_preferred
+///   (
+///     vec![ 240, 360, 1080 ],
+///     vec![ Number( 360 ), Number( 720 ) ],
+///   ),
+///   Ok( vec![ 360, 1080 ] ),
+/// );
+/// ```
+///
+/// ```
+/// # use task_rust::{ find_preferred, Value::* };
+/// assert_eq!
+/// (
+///   find_preferred
+///   (
+///     vec![ 240, 360, 720 ],
+///     vec![ Number( 360 ), Any ],
+///   ),
+///   Ok( vec![ 240, 360, 720 ] ),
+/// );
+/// ```
+pub fn find_preferred< T : Ord + Clone >( available : Vec< T >, preferred : Vec< Value< T > > ) -> Result< Vec< T >, SelectionError >
+{
+  find_matching
+  (
+    available,
+    preferred,
+    | available, pref |
+    {
+      let ( down, up ) = neighbours( available, pref );
+      up.or( down )
+    },
+  )
+}
+
+/// Same as [`find_preferred`] but matches preferred values
+/// according to `strategy` instead of always rounding up.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ find_preferred_with, MatchStrategy, Tie, Value::* };
+/// assert_eq!
+/// (
+///   find_preferred_with
+///   (
+///     vec![ 240, 360, 1080 ],
+///     vec![ Number( 300 ), Number( 900 ) ],
+///     MatchStrategy::Nearest( Tie::Down ),
+///   ),
+///   Ok( vec![ 240, 1080 ] ),
+/// );
+/// ```
+pub fn find_preferred_with< T : Ord + Clone + Distance >
+(
+  available : Vec< T >,
+  preferred : Vec< Value< T > >,
+  strategy : MatchStrategy,
+) -> Result< Vec< T >, SelectionError >
+{
+  find_matching( available, preferred, | available, pref | strategy.pick( available, pref ) )
+}
+
+/// Implements [`find_preferred`] with `pick` choosing the value
+/// of **sorted** `available` matching a preferred one.
+fn find_matching< T : Ord + Clone >
+(
+  available : Vec< T >,
+  preferred : Vec< Value< T > >,
+  pick : impl for< 'a > Fn( &'a [ T ], &T ) -> Option< &'a T >,
+) -> Result< Vec< T >, SelectionError >
+{
+  if preferred.contains( &Value::Any )
+  {
+    Ok( available )
+  }
+  else
+  {
+    let mut result = vec![];
+
+    for pref in numbers( &preferred, Argument::Preferred )?
+    {
+      result.extend( pick( &available, &pref ).cloned() );
+    }
+
+    Ok( ResultOrder::Preference.apply( result ) )
+  }
+}
+
+/// Unwraps every value of `values` into a number, reporting
+/// [`SelectionError::UnexpectedAny`] or
+/// [`SelectionError::UnexpectedConstraint`] for `argument` otherwise.
+pub( crate ) fn numbers< T : Clone >( values : &[ Value< T > ], argument : Argument ) -> Result< Vec< T >, SelectionError >
+{
+  values
+  .iter()
+  .map( | x | match x
+  {
+    Value::Number( n ) => Ok( n.clone() ),
+    Value::Any => Err( SelectionError::UnexpectedAny { argument } ),
+    _ => Err( SelectionError::UnexpectedConstraint { argument } ),
+  })
+  .collect()
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ attempt, attempt_checked, attempt_normalized, attempt_with, numbers, SelectionOptions };
+  use crate::{ Argument, MatchStrategy, ResultOrder, SelectionError, Tie, Value, Value::* };
+
+  // generic tests
+  #[ test ]
+  fn test_u64()
+  {
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 800_u64, 1_500, 4_500, 8_000 ],
+        &[ Number( 1_500 ), Number( 4_500 ), Number( 8_000 ) ],
+        &[ Number( 1_000 ), Number( 5_000 ) ],
+      ),
+      Ok( vec![ 1_500, 8_000 ] ),
+    );
+  }
+
+  #[ test ]
+  fn test_ordinal_enum()
+  {
+    #[ derive( Debug, PartialEq, Eq, PartialOrd, Ord, Clone ) ]
+    enum Quality
+    {
+      Low,
+      Medium,
+      High,
+    }
+
+    assert_eq!
+    (
+      attempt
+      (
+        &[ Quality::Low, Quality::Medium ],
+        &[ Any ],
+        &[ Number( Quality::High ) ],
+      ),
+      Ok( vec![ Quality::Medium ] ),
+    );
+  }
+
+  // error tests
+  #[ test ]
+  fn test_unexpected_any()
+  {
+    assert_eq!
+    (
+      numbers( &[ Number( 240 ), Any ], Argument::Allowed ),
+      Err( SelectionError::UnexpectedAny { argument : Argument::Allowed } ),
+    );
+  }
+
+  // sorting precondition tests
+  #[ test ]
+  fn test_checked_sorted()
+  {
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240, 360, 720 ],
+        &[ Number( 360 ), Number( 720 ) ],
+        &[ Number( 1080 ) ],
+      ),
+      Ok( vec![ 720 ] ),
+    );
+  }
+
+  #[ test ]
+  fn test_checked_unsorted_allowed()
+  {
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240, 360, 720 ],
+        &[ Number( 720 ), Number( 360 ) ],
+        &[ Number( 1080 ) ],
+      ),
+      Err( SelectionError::Unsorted { argument : Argument::Allowed, index : 1 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_checked_duplicate_preferred()
+  {
+    assert_eq!
+    (
+      attempt_checked
+      (
+        &[ 240, 360, 720 ],
+        &[ Any ],
+        &[ Number( 360 ), Number( 360 ) ],
+      ),
+      Err( SelectionError::Unsorted { argument : Argument::Preferred, index : 1 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_normalized_any()
+  {
+    assert_eq!
+    (
+      attempt_normalized
+      (
+        &[ 720, 360, 240 ],
+        &[ Number( 1080 ), Any, Number( 360 ) ],
+        &[ Number( 720 ), Number( 240 ) ],
+      ),
+      Ok( vec![ 240, 720 ] ),
+    );
+  }
+
+  // range tests
+  #[ test ]
+  fn test_range_in_preferred()
+  {
+    assert_eq!
+    (
+      attempt( &[ 240, 360 ], &[ Any ], &[ Value::range( 240 .. ) ] ),
+      Err( SelectionError::UnexpectedConstraint { argument : Argument::Preferred } ),
+    );
+  }
+
+  // comparison tests
+  #[ test ]
+  fn test_comparisons()
+  {
+    assert_eq!
+    (
+      attempt
+      (
+        &[ 240, 360, 480, 720, 1080, 1440 ],
+        &[ Number( 240 ), AtLeast( 720 ), AtMost( 360 ), Not( 240 ) ],
+        &[ Any ],
+      ),
+      Ok( vec![ 360, 720, 1080, 1440 ] ),
+    );
+  }
+
+  #[ test ]
+  fn test_only_exclusions()
+  {
+    assert_eq!
+    (
+      attempt( &[ 240, 480, 720 ], &[ Not( 480 ) ], &[ Number( 480 ) ] ),
+      Ok( vec![ 720 ] ),
+    );
+  }
+
+  // strategy tests
+  #[ test ]
+  fn test_strategies()
+  {
+    let select = | strategy | attempt_with
+    (
+      &[ 240, 360, 720 ],
+      &[ Number( 240 ), Number( 360 ), Number( 720 ) ],
+      &[ Number( 100 ), Number( 300 ), Number( 360 ), Number( 1080 ) ],
+      &SelectionOptions { strategy, ..Default::default() },
+    );
+
+    assert_eq!( select( MatchStrategy::RoundUp ), Ok( vec![ 240, 360, 720 ] ) );
+    assert_eq!( select( MatchStrategy::RoundDown ), Ok( vec![ 240, 360, 720 ] ) );
+    assert_eq!( select( MatchStrategy::Nearest( Tie::Up ) ), Ok( vec![ 240, 360, 720 ] ) );
+    assert_eq!( select( MatchStrategy::Nearest( Tie::Down ) ), Ok( vec![ 240, 360, 720 ] ) );
+    assert_eq!( select( MatchStrategy::ExactOnly ), Ok( vec![ 360 ] ) );
+  }
+
+  #[ test ]
+  fn test_strategy_round_down()
+  {
+    assert_eq!
+    (
+      attempt_with
+      (
+        &[ 240, 360, 720 ],
+        &[ Any ],
+        &[ Number( 300 ), Number( 700 ) ],
+        &SelectionOptions { strategy : MatchStrategy::RoundDown, ..Default::default() },
+      ),
+      Ok( vec![ 240, 360 ] ),
+    );
+  }
+
+  // ordering tests
+  #[ test ]
+  fn test_unsorted_preferred_unique()
+  {
+    assert_eq!
+    (
+      attempt( &[ 300, 720 ], &[ Any ], &[ Number( 240 ), Number( 1080 ), Number( 300 ) ] ),
+      Ok( vec![ 300, 720 ] ),
+    );
+  }
+
+  #[ test ]
+  fn test_orders()
+  {
+    let select = | order | attempt_with
+    (
+      &[ 240, 360, 720 ],
+      &[ Any ],
+      &[ Number( 1080 ), Number( 240 ), Number( 700 ) ],
+      &SelectionOptions { order, ..Default::default() },
+    );
+
+    assert_eq!( select( ResultOrder::Preference ), Ok( vec![ 720, 240 ] ) );
+    assert_eq!( select( ResultOrder::Ascending ), Ok( vec![ 240, 720 ] ) );
+    assert_eq!( select( ResultOrder::Descending ), Ok( vec![ 720, 240 ] ) );
+  }
+
+  #[ test ]
+  fn test_order_any()
+  {
+    assert_eq!
+    (
+      attempt_with
+      (
+        &[ 240, 360, 720 ],
+        &[ Any ],
+        &[ Any ],
+        &SelectionOptions { order : ResultOrder::Descending, ..Default::default() },
+      ),
+      Ok( vec![ 720, 360, 240 ] ),
+    );
+  }
+}
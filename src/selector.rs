@@ -0,0 +1,243 @@
+use std::time::Duration;
+use crate::{ Distance, SelectionError, SelectionOptions, Throughput, Value };
+use crate::select::allows;
+
+/// Thresholds keeping a [`Selector`] from switching back and forth
+/// when throughput hovers around the bitrate of a value.
+#[ derive( Debug, PartialEq, Clone, Copy ) ]
+pub struct Hysteresis
+{
+  /// Scales the throughput safety factor when switching up, so
+  /// e.g. `0.8` switches up only with 25% of headroom left.
+  pub up : f64,
+  /// Scales the throughput safety factor when switching down, so
+  /// e.g. `1.2` keeps the current value until it exceeds the
+  /// budget by 20%.
+  pub down : f64,
+  /// Time to hold the current value before switching up.
+  pub hold_up : Duration,
+  /// Time to hold the current value before switching down.
+  pub hold_down : Duration,
+}
+
+/// Switches as soon as the selection changes.
+impl Default for Hysteresis
+{
+  fn default() -> Self
+  {
+    Self { up : 1.0, down : 1.0, hold_up : Duration::ZERO, hold_down : Duration::ZERO }
+  }
+}
+
+/// Selects one value at a time for successive decisions, e.g. each
+/// segment of a stream, remembering the previous choice.
+///
+/// Each decision selects like [`Throughput::attempt`] and takes the
+/// first selected value, or the highest one if `preferred` contains
+/// [`Value::Any`]. A higher value replaces the current one only
+/// if it is still selected with the safety factor scaled by
+/// [`Hysteresis::up`] and the current value was held for
+/// [`Hysteresis::hold_up`], and a lower value likewise with
+/// [`Hysteresis::down`] and [`Hysteresis::hold_down`]. A current value
+/// which is no longer available or allowed is replaced right away.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use task_rust::{ Hysteresis, Selector, Throughput, Value::* };
+/// let available = [ ( 360, 800_000 ), ( 720, 2_500_000 ), ( 1080, 5_000_000 ) ];
+/// let mut selector = Selector::new( vec![ Any ], vec![ Number( 1080 ) ] );
+/// selector.hysteresis = Hysteresis { up : 0.8, down : 1.2, ..Default::default() };
+///
+/// let mut decide = | measured, seconds |
+/// {
+///   let throughput = Throughput { measured, safety : 1.0 };
+///   selector.select( &available, &throughput, Duration::from_secs( seconds ) ).unwrap()
+/// };
+/// assert_eq!( decide( 3_000_000, 0 ), Some( 360 ) );
+/// assert_eq!( decide( 3_200_000, 2 ), Some( 720 ) );
+/// assert_eq!( decide( 2_400_000, 4 ), Some( 720 ) );
+/// assert_eq!( decide( 2_000_000, 6 ), Some( 360 ) );
+/// ```
+#[ derive( Debug, PartialEq, Clone ) ]
+pub struct Selector< T = i32 >
+{
+  pub allowed : Vec< Value< T > >,
+  pub preferred : Vec< Value< T > >,
+  pub options : SelectionOptions,
+  pub hysteresis : Hysteresis,
+  /// Current value and the time it was selected at.
+  current : Option< ( T, Duration ) >,
+}
+
+impl< T : Ord + Clone + Distance > Selector< T >
+{
+  /// Creates selector without a current value, using default
+  /// options and [`Hysteresis`].
+  pub fn new( allowed : Vec< Value< T > >, preferred : Vec< Value< T > > ) -> Self
+  {
+    Self
+    {
+      allowed,
+      preferred,
+      options : SelectionOptions::default(),
+      hysteresis : Hysteresis::default(),
+      current : None,
+    }
+  }
+
+  /// Returns the current value, if any.
+  pub fn current( &self ) -> Option< &T >
+  {
+    self.current.as_ref().map( | ( value, _ ) | value )
+  }
+
+  /// Forgets the current value, so the next decision
+  /// is made as if it were the first one.
+  pub fn reset( &mut self )
+  {
+    self.current = None;
+  }
+
+  /// Decides which value of `available`, given with its bitrate in
+  /// bit/s, to use at time `now` and returns it. `now` is any
+  /// monotonic time, e.g. the position in the stream.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if [`Throughput::attempt`] fails.
+  pub fn select
+  (
+    &mut self,
+    available : &[ ( T, u64 ) ],
+    throughput : &Throughput,
+    now : Duration,
+  ) -> Result< Option< T >, SelectionError >
+  {
+    let target = | scale : f64 | -> Result< Option< T >, SelectionError >
+    {
+      let throughput = Throughput { safety : throughput.safety * scale, ..*throughput };
+      let mut selected = throughput.attempt( available, &self.allowed, &self.preferred, &self.options )?.selected.into_iter();
+      if self.preferred.contains( &Value::Any )
+      {
+        Ok( selected.max() )
+      }
+      else
+      {
+        Ok( selected.next() )
+      }
+    };
+    let up = target( self.hysteresis.up )?;
+    let down = target( self.hysteresis.down )?;
+
+    let next = match &self.current
+    {
+      Some( ( current, since ) ) if available.iter().any( | ( x, _ ) | x == current ) && allows( &self.allowed, current ) =>
+      {
+        let held = now.saturating_sub( *since );
+        match ( up, down )
+        {
+          ( Some( up ), _ ) if up > *current && held >= self.hysteresis.hold_up => Some( up ),
+          ( _, Some( down ) ) if down < *current && held >= self.hysteresis.hold_down => Some( down ),
+          _ => Some( current.clone() ),
+        }
+      }
+      _ => up.or( down ),
+    };
+
+    if next.as_ref() != self.current()
+    {
+      self.current = next.clone().map( | value | ( value, now ) );
+    }
+    Ok( next )
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use std::time::Duration;
+  use super::{ Hysteresis, Selector };
+  use crate::{ Throughput, Value::* };
+
+  const LADDER : [ ( u32, u64 ); 3 ] = [ ( 360, 800_000 ), ( 720, 2_500_000 ), ( 1080, 5_000_000 ) ];
+
+  /// Returns decisions of `selector` for throughput measured every second.
+  fn decisions( selector : &mut Selector< u32 >, measured : &[ u64 ] ) -> Vec< Option< u32 > >
+  {
+    measured
+    .iter()
+    .zip( 0 .. )
+    .map( | ( measured, second ) |
+    {
+      let throughput = Throughput { measured : *measured, safety : 1.0 };
+      selector.select( &LADDER, &throughput, Duration::from_secs( second ) ).unwrap()
+    })
+    .collect()
+  }
+
+  #[ test ]
+  fn test_without_hysteresis()
+  {
+    let mut selector = Selector::new( vec![ Any ], vec![ Number( 1080 ) ] );
+    assert_eq!
+    (
+      decisions( &mut selector, &[ 2_400_000, 2_600_000, 2_400_000, 2_600_000 ] ),
+      vec![ Some( 360 ), Some( 720 ), Some( 360 ), Some( 720 ) ],
+    );
+    assert_eq!( selector.current(), Some( &720 ) );
+    selector.reset();
+    assert_eq!( selector.current(), None );
+  }
+
+  #[ test ]
+  fn test_thresholds()
+  {
+    let mut selector = Selector::new( vec![ Any ], vec![ Number( 1080 ) ] );
+    selector.hysteresis = Hysteresis { up : 0.8, down : 1.2, ..Default::default() };
+    assert_eq!
+    (
+      decisions( &mut selector, &[ 2_600_000, 3_200_000, 2_400_000, 2_600_000, 2_000_000, 2_600_000 ] ),
+      vec![ Some( 360 ), Some( 720 ), Some( 720 ), Some( 720 ), Some( 360 ), Some( 360 ) ],
+    );
+  }
+
+  #[ test ]
+  fn test_hold_times()
+  {
+    let mut selector = Selector::new( vec![ Any ], vec![ Number( 1080 ) ] );
+    selector.hysteresis = Hysteresis { hold_up : Duration::from_secs( 3 ), hold_down : Duration::from_secs( 1 ), ..Default::default() };
+    assert_eq!
+    (
+      decisions( &mut selector, &[ 1_000_000, 6_000_000, 6_000_000, 6_000_000, 1_000_000, 6_000_000 ] ),
+      vec![ Some( 360 ), Some( 360 ), Some( 360 ), Some( 1080 ), Some( 360 ), Some( 360 ) ],
+    );
+  }
+
+  #[ test ]
+  fn test_any_preferred()
+  {
+    let mut selector = Selector::new( vec![ Any ], vec![ Any ] );
+    assert_eq!
+    (
+      decisions( &mut selector, &[ 1_000_000, 3_000_000, 6_000_000, 100_000 ] ),
+      vec![ Some( 360 ), Some( 720 ), Some( 1080 ), Some( 360 ) ],
+    );
+  }
+
+  #[ test ]
+  fn test_disallowed_current()
+  {
+    let mut selector = Selector::new( vec![ Any ], vec![ Number( 1080 ) ] );
+    selector.hysteresis.hold_down = Duration::from_secs( 60 );
+    assert_eq!( decisions( &mut selector, &[ 6_000_000 ] ), vec![ Some( 1080 ) ] );
+
+    selector.allowed = vec![ Not( 1080 ) ];
+    assert_eq!( decisions( &mut selector, &[ 6_000_000 ] ), vec![ Some( 720 ) ] );
+
+    selector.allowed = vec![ Number( 480 ) ];
+    assert_eq!( decisions( &mut selector, &[ 6_000_000 ] ), vec![ None ] );
+    assert_eq!( selector.current(), None );
+  }
+}
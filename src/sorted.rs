@@ -0,0 +1,314 @@
+use crate::{ Argument, SelectionError, Value };
+
+/// Checks that `values` are sorted in strictly ascending order,
+/// reporting the index of the first offending value of `argument`.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ check_sorted, Argument, SelectionError };
+/// assert_eq!( check_sorted( &[ 240, 360, 720 ], Argument::Available ), Ok( () ) );
+/// assert_eq!
+/// (
+///   check_sorted( &[ 240, 720, 360 ], Argument::Available ),
+///   Err( SelectionError::Unsorted { argument : Argument::Available, index : 2 } ),
+/// );
+/// ```
+pub fn check_sorted< T : Ord >( values : &[ T ], argument : Argument ) -> Result< (), SelectionError >
+{
+  match values.windows( 2 ).position( | pair | pair[ 0 ] >= pair[ 1 ] )
+  {
+    Some( index ) => Err( SelectionError::Unsorted { argument, index : index + 1 } ),
+    None => Ok( () ),
+  }
+}
+
+/// Same as [`check_sorted`] for lists of [`Value`]s. [`Value::Any`]
+/// and ranges may appear anywhere, only numbers have to be in order.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ check_sorted_values, Argument, SelectionError, Value::* };
+/// assert_eq!( check_sorted_values( &[ Number( 360 ), Any, Number( 720 ) ], Argument::Allowed ), Ok( () ) );
+/// assert_eq!
+/// (
+///   check_sorted_values( &[ Number( 360 ), Any, Number( 360 ) ], Argument::Allowed ),
+///   Err( SelectionError::Unsorted { argument : Argument::Allowed, index : 2 } ),
+/// );
+/// ```
+pub fn check_sorted_values< T : Ord >( values : &[ Value< T > ], argument : Argument ) -> Result< (), SelectionError >
+{
+  let mut previous = None;
+
+  for ( index, value ) in values.iter().enumerate()
+  {
+    if let Value::Number( n ) = value
+    {
+      if previous.is_some_and( | p | p >= n )
+      {
+        return Err( SelectionError::Unsorted { argument, index } );
+      }
+      previous = Some( n );
+    }
+  }
+
+  Ok( () )
+}
+
+/// Sorts `values` in ascending order and removes duplicates.
+fn normalize< T : Ord >( mut values : Vec< T > ) -> Vec< T >
+{
+  values.sort();
+  values.dedup();
+  values
+}
+
+/// Sorts numbers of `values` in ascending order and removes duplicates,
+/// placing other values after the numbers. A list containing
+/// [`Value::Any`] collapses to the wildcard, keeping only exclusions
+/// ([`Value::Not`]) which take precedence over it.
+fn normalize_values< T : Ord >( values : Vec< Value< T > > ) -> Vec< Value< T > >
+{
+  let any = values.contains( &Value::Any );
+  let mut numbers = vec![];
+  let mut others = vec![];
+  for value in values
+  {
+    match value
+    {
+      Value::Number( n ) if !any => numbers.push( n ),
+      value @ Value::Not( _ ) if !others.contains( &value ) => others.push( value ),
+      value if !any && !others.contains( &value ) => others.push( value ),
+      _ => {}
+    }
+  }
+
+  if any
+  {
+    others.insert( 0, Value::Any );
+  }
+  normalize( numbers ).into_iter().map( Value::Number ).chain( others ).collect()
+}
+
+/// Set of values sorted in strictly ascending order. Can only be built
+/// by sorting or by validating, so it is always a correct `available`
+/// argument for [`attempt_sorted`](crate::attempt_sorted).
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::SortedSet;
+/// let set = SortedSet::new( vec![ 720, 240, 720 ] );
+/// assert_eq!( &*set, &[ 240, 720 ] );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct SortedSet< T >( Vec< T > );
+
+impl< T : Ord > SortedSet< T >
+{
+  /// Sorts `values` and removes duplicates.
+  pub fn new( values : Vec< T > ) -> Self
+  {
+    Self( normalize( values ) )
+  }
+
+  /// Wraps `values` if they are already sorted.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError::Unsorted`] for `argument` otherwise.
+  pub fn checked( values : Vec< T >, argument : Argument ) -> Result< Self, SelectionError >
+  {
+    check_sorted( &values, argument )?;
+    Ok( Self( values ) )
+  }
+}
+
+impl< T > SortedSet< T >
+{
+  /// Returns wrapped values.
+  pub fn into_inner( self ) -> Vec< T >
+  {
+    self.0
+  }
+}
+
+impl< T > std::ops::Deref for SortedSet< T >
+{
+  type Target = [ T ];
+
+  fn deref( &self ) -> &[ T ]
+  {
+    &self.0
+  }
+}
+
+/// List of [`Value`]s which is either [`Value::Any`] followed by
+/// exclusions, or numbers sorted in strictly ascending order followed
+/// by constraints such as ranges. Can only be built by
+/// sorting or by validating, so it is always a correct `allowed` or
+/// `preferred` argument for [`attempt_sorted`](crate::attempt_sorted).
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ SortedValues, Value::* };
+/// let values = SortedValues::new( vec![ Number( 720 ), Number( 360 ) ] );
+/// assert_eq!( &*values, &[ Number( 360 ), Number( 720 ) ] );
+///
+/// let values = SortedValues::new( vec![ Number( 720 ), Any ] );
+/// assert!( values.is_any() );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct SortedValues< T >( Vec< Value< T > > );
+
+impl< T : Ord > SortedValues< T >
+{
+  /// Sorts numbers of `values` and removes duplicates.
+  /// Values containing [`Value::Any`] collapse to the wildcard.
+  pub fn new( values : Vec< Value< T > > ) -> Self
+  {
+    Self( normalize_values( values ) )
+  }
+
+  /// Wraps `values` if their numbers are already sorted.
+  /// Values containing [`Value::Any`] collapse to the wildcard.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError::Unsorted`] for `argument` otherwise.
+  pub fn checked( values : Vec< Value< T > >, argument : Argument ) -> Result< Self, SelectionError >
+  {
+    check_sorted_values( &values, argument )?;
+    Ok( Self::new( values ) )
+  }
+}
+
+impl< T > SortedValues< T >
+{
+  /// Returns wildcard matching every number.
+  pub fn any() -> Self
+  {
+    Self( vec![ Value::Any ] )
+  }
+
+  /// Returns `true` if the values are the [`Value::Any`] wildcard
+  /// without exclusions.
+  pub fn is_any( &self ) -> bool
+  {
+    matches!( self.0.as_slice(), [ Value::Any ] )
+  }
+
+  /// Returns wrapped values.
+  pub fn into_inner( self ) -> Vec< Value< T > >
+  {
+    self.0
+  }
+}
+
+impl< T > std::ops::Deref for SortedValues< T >
+{
+  type Target = [ Value< T > ];
+
+  fn deref( &self ) -> &[ Value< T > ]
+  {
+    &self.0
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ check_sorted, check_sorted_values, normalize, normalize_values, SortedSet, SortedValues };
+  use crate::{ Argument, SelectionError, Value, Value::* };
+
+  #[ test ]
+  fn test_check_sorted_duplicate()
+  {
+    assert_eq!
+    (
+      check_sorted( &[ 240, 360, 360 ], Argument::Available ),
+      Err( SelectionError::Unsorted { argument : Argument::Available, index : 2 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_check_sorted_empty()
+  {
+    assert_eq!( check_sorted::< i32 >( &[], Argument::Available ), Ok( () ) );
+  }
+
+  #[ test ]
+  fn test_check_sorted_values_unordered()
+  {
+    assert_eq!
+    (
+      check_sorted_values( &[ Any, Number( 720 ), Number( 360 ) ], Argument::Preferred ),
+      Err( SelectionError::Unsorted { argument : Argument::Preferred, index : 2 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_normalize()
+  {
+    assert_eq!( normalize( vec![ 720, 240, 720, 360 ] ), vec![ 240, 360, 720 ] );
+  }
+
+  #[ test ]
+  fn test_normalize_values()
+  {
+    assert_eq!
+    (
+      normalize_values( vec![ Number( 720 ), Number( 360 ), Number( 720 ) ] ),
+      vec![ Number( 360 ), Number( 720 ) ],
+    );
+    assert_eq!( normalize_values( vec![ Number( 720 ), Any ] ), vec![ Any ] );
+  }
+
+  #[ test ]
+  fn test_sorted_set_checked()
+  {
+    assert_eq!( SortedSet::checked( vec![ 240, 360 ], Argument::Available ), Ok( SortedSet::new( vec![ 360, 240 ] ) ) );
+    assert_eq!
+    (
+      SortedSet::checked( vec![ 360, 240 ], Argument::Available ),
+      Err( SelectionError::Unsorted { argument : Argument::Available, index : 1 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_sorted_values_checked_any()
+  {
+    assert_eq!
+    (
+      SortedValues::checked( vec![ Number( 240 ), Any, Number( 360 ) ], Argument::Allowed ),
+      Ok( SortedValues::any() ),
+    );
+    assert_eq!
+    (
+      SortedValues::checked( vec![ Number( 360 ), Any, Number( 240 ) ], Argument::Allowed ),
+      Err( SelectionError::Unsorted { argument : Argument::Allowed, index : 2 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_normalize_values_exclusions()
+  {
+    assert_eq!
+    (
+      normalize_values( vec![ Not( 480 ), Number( 720 ), AtLeast( 360 ), Any, Not( 480 ) ] ),
+      vec![ Any, Not( 480 ) ],
+    );
+  }
+
+  #[ test ]
+  fn test_normalize_values_ranges()
+  {
+    assert_eq!
+    (
+      normalize_values( vec![ Value::range( 1080 .. ), Number( 720 ), Value::range( 1080 .. ), Number( 360 ) ] ),
+      vec![ Number( 360 ), Number( 720 ), Value::range( 1080 .. ) ],
+    );
+  }
+}
@@ -0,0 +1,191 @@
+use crate::ParseError;
+
+/// Measures how far apart two values are. Used by
+/// [`MatchStrategy::Nearest`] and implemented for primitive integers.
+pub trait Distance
+{
+  type Output : Ord;
+
+  /// Returns the absolute difference between `self` and `other`.
+  fn distance( &self, other : &Self ) -> Self::Output;
+}
+
+macro_rules! impl_distance
+{
+  ( $( $t : ty => $output : ty ),* $(,)? ) =>
+  {
+    $(
+      impl Distance for $t
+      {
+        type Output = $output;
+
+        fn distance( &self, other : &Self ) -> $output
+        {
+          self.abs_diff( *other )
+        }
+      }
+    )*
+  };
+}
+
+impl_distance!
+(
+  i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize,
+  u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize,
+);
+
+/// Which way [`MatchStrategy::Nearest`] goes when
+/// two available values are equally close.
+#[ derive( Debug, PartialEq, Eq, Clone, Copy, Default ) ]
+pub enum Tie
+{
+  #[ default ]
+  Up,
+  Down,
+}
+
+/// How a preferred value is matched against available ones
+/// when there is no exact match.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ MatchStrategy, Tie };
+/// let available = [ 240, 360, 720 ];
+/// assert_eq!( MatchStrategy::RoundUp.pick( &available, &480 ), Some( &720 ) );
+/// assert_eq!( MatchStrategy::RoundDown.pick( &available, &480 ), Some( &360 ) );
+/// assert_eq!( MatchStrategy::Nearest( Tie::Up ).pick( &available, &480 ), Some( &360 ) );
+/// assert_eq!( MatchStrategy::ExactOnly.pick( &available, &480 ), None );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone, Copy, Default ) ]
+pub enum MatchStrategy
+{
+  /// Smallest value equal or greater than preferred,
+  /// otherwise the largest available one.
+  #[ default ]
+  RoundUp,
+  /// Largest value equal or smaller than preferred,
+  /// otherwise the smallest available one.
+  RoundDown,
+  /// Value closest to preferred, breaking ties with [`Tie`].
+  Nearest( Tie ),
+  /// Only value equal to preferred.
+  ExactOnly,
+}
+
+impl MatchStrategy
+{
+  /// Picks the value of **sorted** `available` matching `preferred`.
+  /// Returns `None` if `available` is empty or, for
+  /// [`MatchStrategy::ExactOnly`], if there is no such value.
+  pub fn pick< 'a, T : Ord + Distance >( &self, available : &'a [ T ], preferred : &T ) -> Option< &'a T >
+  {
+    let ( down, up ) = neighbours( available, preferred );
+    match self
+    {
+      MatchStrategy::RoundUp => up.or( down ),
+      MatchStrategy::RoundDown => up.filter( | x | *x == preferred ).or( down ).or( up ),
+      MatchStrategy::Nearest( tie ) => match ( down, up )
+      {
+        ( Some( down ), Some( up ) ) => match down.distance( preferred ).cmp( &up.distance( preferred ) )
+        {
+          std::cmp::Ordering::Less => Some( down ),
+          std::cmp::Ordering::Greater => Some( up ),
+          std::cmp::Ordering::Equal if *tie == Tie::Up => Some( up ),
+          std::cmp::Ordering::Equal => Some( down ),
+        },
+        ( down, up ) => up.or( down ),
+      },
+      MatchStrategy::ExactOnly => up.filter( | x | *x == preferred ),
+    }
+  }
+}
+
+/// Returns the largest value of **sorted** `available` smaller than
+/// `preferred` and the smallest one equal or greater than it.
+pub( crate ) fn neighbours< 'a, T : Ord >( available : &'a [ T ], preferred : &T ) -> ( Option< &'a T >, Option< &'a T > )
+{
+  let index = available.partition_point( | x | x < preferred );
+  ( index.checked_sub( 1 ).map( | i | &available[ i ] ), available.get( index ) )
+}
+
+/// Parses `round-up`, `round-down`, `nearest` (ties go up),
+/// `nearest-down` (ties go down) or `exact`.
+impl std::str::FromStr for MatchStrategy
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    match s.trim()
+    {
+      "round-up" => Ok( MatchStrategy::RoundUp ),
+      "round-down" => Ok( MatchStrategy::RoundDown ),
+      "nearest" => Ok( MatchStrategy::Nearest( Tie::Up ) ),
+      "nearest-down" => Ok( MatchStrategy::Nearest( Tie::Down ) ),
+      "exact" => Ok( MatchStrategy::ExactOnly ),
+      token => Err( ParseError::InvalidValue { token : token.to_string(), offset : s.len() - s.trim_start().len() } ),
+    }
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ MatchStrategy, Tie };
+  use crate::ParseError;
+
+  const AVAILABLE : [ i32; 3 ] = [ 240, 360, 720 ];
+
+  #[ test ]
+  fn test_round_up()
+  {
+    assert_eq!( MatchStrategy::RoundUp.pick( &AVAILABLE, &360 ), Some( &360 ) );
+    assert_eq!( MatchStrategy::RoundUp.pick( &AVAILABLE, &100 ), Some( &240 ) );
+    assert_eq!( MatchStrategy::RoundUp.pick( &AVAILABLE, &1080 ), Some( &720 ) );
+  }
+
+  #[ test ]
+  fn test_round_down()
+  {
+    assert_eq!( MatchStrategy::RoundDown.pick( &AVAILABLE, &360 ), Some( &360 ) );
+    assert_eq!( MatchStrategy::RoundDown.pick( &AVAILABLE, &700 ), Some( &360 ) );
+    assert_eq!( MatchStrategy::RoundDown.pick( &AVAILABLE, &100 ), Some( &240 ) );
+    assert_eq!( MatchStrategy::RoundDown.pick( &AVAILABLE, &1080 ), Some( &720 ) );
+  }
+
+  #[ test ]
+  fn test_nearest()
+  {
+    assert_eq!( MatchStrategy::Nearest( Tie::Up ).pick( &AVAILABLE, &700 ), Some( &720 ) );
+    assert_eq!( MatchStrategy::Nearest( Tie::Up ).pick( &AVAILABLE, &250 ), Some( &240 ) );
+    assert_eq!( MatchStrategy::Nearest( Tie::Up ).pick( &AVAILABLE, &300 ), Some( &360 ) );
+    assert_eq!( MatchStrategy::Nearest( Tie::Down ).pick( &AVAILABLE, &300 ), Some( &240 ) );
+    assert_eq!( MatchStrategy::Nearest( Tie::Down ).pick( &AVAILABLE, &1080 ), Some( &720 ) );
+    assert_eq!( MatchStrategy::Nearest( Tie::Down ).pick( &[ -10_i8, 120 ], &-128 ), Some( &-10 ) );
+  }
+
+  #[ test ]
+  fn test_exact_only()
+  {
+    assert_eq!( MatchStrategy::ExactOnly.pick( &AVAILABLE, &360 ), Some( &360 ) );
+    assert_eq!( MatchStrategy::ExactOnly.pick( &AVAILABLE, &361 ), None );
+  }
+
+  #[ test ]
+  fn test_empty()
+  {
+    assert_eq!( MatchStrategy::Nearest( Tie::Up ).pick::< i32 >( &[], &360 ), None );
+  }
+
+  #[ test ]
+  fn test_parse()
+  {
+    assert_eq!( "nearest-down".parse(), Ok( MatchStrategy::Nearest( Tie::Down ) ) );
+    assert_eq!
+    (
+      " closest".parse::< MatchStrategy >(),
+      Err( ParseError::InvalidValue { token : "closest".to_string(), offset : 1 } ),
+    );
+  }
+}
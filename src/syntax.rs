@@ -0,0 +1,360 @@
+use std::ops::Bound;
+use crate::value::fmt_range;
+use crate::{ ParseError, Value };
+
+impl< T : std::fmt::Display > std::fmt::Display for Value< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      Value::Number( n ) => write!( f, "{}", n ),
+      Value::Any => write!( f, "any" ),
+      Value::Range { min, max } => fmt_range( f, min, max, | f, n | write!( f, "{}", n ) ),
+      Value::AtLeast( n ) => write!( f, ">={}", n ),
+      Value::AtMost( n ) => write!( f, "<={}", n ),
+      Value::Not( n ) => write!( f, "!{}", n ),
+    }
+  }
+}
+
+/// Parses `any`, a number or a range of numbers written like in Rust:
+/// `360..=1080`, `360..1080`, `360..`, `..=1080`. A minimum followed
+/// by `<`, as in `360<..`, is excluded from the range. Comparisons
+/// are written as `>=720`, `<=1080` and `!480`.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ ParseError, Value };
+/// assert_eq!( "720".parse(), Ok( Value::Number( 720 ) ) );
+/// assert_eq!( " any ".parse::< Value >(), Ok( Value::Any ) );
+/// assert_eq!( "360..=1080".parse(), Ok( Value::range( 360 ..= 1080 ) ) );
+/// assert_eq!( "!480".parse(), Ok( Value::Not( 480 ) ) );
+/// assert_eq!
+/// (
+///   "abc".parse::< Value >(),
+///   Err( ParseError::InvalidValue { token : "abc".to_string(), offset : 0 } ),
+/// );
+/// ```
+impl< T : std::str::FromStr > std::str::FromStr for Value< T >
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    let ( offset, token ) = trim( 0, s );
+    parse_value( offset, token )
+  }
+}
+
+/// List of [`Value`]s written as comma separated values,
+/// optionally enclosed in square brackets: `240, 360, any`
+/// or `[360, 720]`.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ ParseError, ValueList, Value::* };
+/// let list : ValueList = "[ 240, 360, any ]".parse().unwrap();
+/// assert_eq!( list.0, vec![ Number( 240 ), Number( 360 ), Any ] );
+/// assert_eq!( list.to_string(), "240, 360, any" );
+///
+/// assert_eq!
+/// (
+///   "240, 3x0".parse::< ValueList >(),
+///   Err( ParseError::InvalidValue { token : "3x0".to_string(), offset : 5 } ),
+/// );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct ValueList< T = i32 >( pub Vec< Value< T > > );
+
+impl< T : std::str::FromStr > std::str::FromStr for ValueList< T >
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    tokens( s )?
+    .into_iter()
+    .map( | ( offset, token ) | parse_value( offset, token ) )
+    .collect::< Result< _, _ > >()
+    .map( ValueList )
+  }
+}
+
+impl< T : std::fmt::Display > std::fmt::Display for ValueList< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    join( f, &self.0 )
+  }
+}
+
+/// List of numbers written in the same syntax as [`ValueList`]
+/// but without `any`, e.g. an `available` set.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ NumberList, ParseError };
+/// let list : NumberList = "240, 360, 720".parse().unwrap();
+/// assert_eq!( list.0, vec![ 240, 360, 720 ] );
+///
+/// assert_eq!
+/// (
+///   "240, any".parse::< NumberList >(),
+///   Err( ParseError::InvalidValue { token : "any".to_string(), offset : 5 } ),
+/// );
+/// ```
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct NumberList< T = i32 >( pub Vec< T > );
+
+impl< T : std::str::FromStr > std::str::FromStr for NumberList< T >
+{
+  type Err = ParseError;
+
+  fn from_str( s : &str ) -> Result< Self, ParseError >
+  {
+    tokens( s )?
+    .into_iter()
+    .map( | ( offset, token ) | token.parse().map_err( | _ | invalid( offset, token ) ) )
+    .collect::< Result< _, _ > >()
+    .map( NumberList )
+  }
+}
+
+impl< T : std::fmt::Display > std::fmt::Display for NumberList< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    join( f, &self.0 )
+  }
+}
+
+/// Writes `items` separated by commas.
+fn join< T : std::fmt::Display >( f : &mut std::fmt::Formatter< '_ >, items : &[ T ] ) -> std::fmt::Result
+{
+  for ( index, item ) in items.iter().enumerate()
+  {
+    if index > 0
+    {
+      write!( f, ", " )?;
+    }
+    write!( f, "{}", item )?;
+  }
+  Ok( () )
+}
+
+/// Parses trimmed `token` starting at `offset`.
+fn parse_value< T : std::str::FromStr >( offset : usize, token : &str ) -> Result< Value< T >, ParseError >
+{
+  let number = | text : &str | text.trim().parse().map_err( | _ | invalid( offset, token ) );
+
+  if let Some( n ) = token.strip_prefix( ">=" )
+  {
+    return number( n ).map( Value::AtLeast );
+  }
+  if let Some( n ) = token.strip_prefix( "<=" )
+  {
+    return number( n ).map( Value::AtMost );
+  }
+  if let Some( n ) = token.strip_prefix( '!' )
+  {
+    return number( n ).map( Value::Not );
+  }
+  if let Some( ( start, end ) ) = token.split_once( ".." )
+  {
+    let min = match start.trim().strip_suffix( '<' )
+    {
+      Some( start ) => Bound::Excluded( number( start )? ),
+      None if start.trim().is_empty() => Bound::Unbounded,
+      None => Bound::Included( number( start )? ),
+    };
+    let max = match end.trim().strip_prefix( '=' )
+    {
+      Some( end ) => Bound::Included( number( end )? ),
+      None if end.trim().is_empty() => Bound::Unbounded,
+      None => Bound::Excluded( number( end )? ),
+    };
+    return Ok( Value::Range { min, max } );
+  }
+
+  match token
+  {
+    "any" => Ok( Value::Any ),
+    _ => number( token ).map( Value::Number ),
+  }
+}
+
+fn invalid( offset : usize, token : &str ) -> ParseError
+{
+  if token.is_empty()
+  {
+    ParseError::EmptyValue { offset }
+  }
+  else
+  {
+    ParseError::InvalidValue { token : token.to_string(), offset }
+  }
+}
+
+/// Trims whitespace around `s` which starts at `offset` in the
+/// parsed string, returning the trimmed slice with its offset.
+fn trim( offset : usize, s : &str ) -> ( usize, &str )
+{
+  let start = s.len() - s.trim_start().len();
+  ( offset + start, s.trim() )
+}
+
+/// Splits comma separated list, optionally enclosed in brackets,
+/// into trimmed tokens with their offsets.
+fn tokens( s : &str ) -> Result< Vec< ( usize, &str ) >, ParseError >
+{
+  let ( mut offset, mut body ) = trim( 0, s );
+
+  match ( body.strip_prefix( '[' ), body.strip_suffix( ']' ) )
+  {
+    ( Some( _ ), Some( _ ) ) if body.len() >= 2 =>
+    {
+      body = &body[ 1 .. body.len() - 1 ];
+      offset += 1;
+    }
+    ( Some( _ ), _ ) => return Err( ParseError::UnbalancedBracket { offset } ),
+    ( None, Some( _ ) ) => return Err( ParseError::UnbalancedBracket { offset : offset + body.len() - 1 } ),
+    ( None, None ) => {}
+  }
+
+  if body.trim().is_empty()
+  {
+    return Ok( vec![] );
+  }
+
+  let mut result = vec![];
+  for token in body.split( ',' )
+  {
+    let ( start, trimmed ) = trim( offset, token );
+    if let Some( bracket ) = trimmed.find( [ '[', ']' ] )
+    {
+      return Err( ParseError::UnbalancedBracket { offset : start + bracket } );
+    }
+    result.push( ( start, trimmed ) );
+    offset += token.len() + 1;
+  }
+  Ok( result )
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use std::ops::Bound;
+  use crate::{ NumberList, ParseError, Value, ValueList, Value::* };
+
+  #[ test ]
+  fn test_display_value()
+  {
+    assert_eq!( Number( 720 ).to_string(), "720" );
+    assert_eq!( Value::< i32 >::Any.to_string(), "any" );
+  }
+
+  #[ test ]
+  fn test_parse_list()
+  {
+    assert_eq!
+    (
+      "240,360,any".parse(),
+      Ok( ValueList( vec![ Number( 240 ), Number( 360 ), Any ] ) ),
+    );
+    assert_eq!( "[360, 720]".parse(), Ok( ValueList( vec![ Number( 360 ), Number( 720 ) ] ) ) );
+    assert_eq!( "[]".parse(), Ok( ValueList::< i32 >( vec![] ) ) );
+    assert_eq!( "  ".parse(), Ok( NumberList::< i32 >( vec![] ) ) );
+  }
+
+  #[ test ]
+  fn test_parse_list_errors()
+  {
+    assert_eq!( "240,,360".parse::< ValueList >(), Err( ParseError::EmptyValue { offset : 4 } ) );
+    assert_eq!( "240, 360,".parse::< ValueList >(), Err( ParseError::EmptyValue { offset : 9 } ) );
+    assert_eq!( " [240, 360".parse::< ValueList >(), Err( ParseError::UnbalancedBracket { offset : 1 } ) );
+    assert_eq!( "240, 360]".parse::< ValueList >(), Err( ParseError::UnbalancedBracket { offset : 8 } ) );
+    assert_eq!( "[240, [360]]".parse::< ValueList >(), Err( ParseError::UnbalancedBracket { offset : 6 } ) );
+    assert_eq!
+    (
+      "[ 240, -1x ]".parse::< NumberList< u32 > >(),
+      Err( ParseError::InvalidValue { token : "-1x".to_string(), offset : 7 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_parse_range()
+  {
+    assert_eq!
+    (
+      "[ 240, 360 .. 720, 1080.., ..=480, 480<..=720 ]".parse(),
+      Ok
+      (
+        ValueList
+        (
+          vec!
+          [
+            Number( 240 ),
+            Value::range( 360 .. 720 ),
+            Value::range( 1080 .. ),
+            Value::range( ..= 480 ),
+            Range { min : Bound::Excluded( 480 ), max : Bound::Included( 720 ) },
+          ]
+        )
+      ),
+    );
+    assert_eq!( "..".parse(), Ok( Value::< i32 >::range( .. ) ) );
+    assert_eq!
+    (
+      "240, 360..=".parse::< ValueList >(),
+      Err( ParseError::InvalidValue { token : "360..=".to_string(), offset : 5 } ),
+    );
+    assert_eq!
+    (
+      "1..2..3".parse::< Value >(),
+      Err( ParseError::InvalidValue { token : "1..2..3".to_string(), offset : 0 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_parse_comparison()
+  {
+    assert_eq!
+    (
+      ">=720, <= 1080, !480".parse(),
+      Ok( ValueList( vec![ AtLeast( 720 ), AtMost( 1080 ), Not( 480 ) ] ) ),
+    );
+    assert_eq!
+    (
+      "240, !!480".parse::< ValueList >(),
+      Err( ParseError::InvalidValue { token : "!!480".to_string(), offset : 5 } ),
+    );
+    assert_eq!
+    (
+      ">=".parse::< Value >(),
+      Err( ParseError::InvalidValue { token : ">=".to_string(), offset : 0 } ),
+    );
+  }
+
+  #[ test ]
+  fn test_round_trip()
+  {
+    let list = ValueList
+    (
+      vec!
+      [
+        Number( 240 ),
+        Any,
+        Value::range( 360 ..= 720 ),
+        Range { min : Bound::Excluded( 1 ), max : Bound::Unbounded },
+        AtLeast( 720 ),
+        AtMost( 1080 ),
+        Not( 480 ),
+      ]
+    );
+    assert_eq!( list.to_string().parse(), Ok( list ) );
+  }
+}
@@ -0,0 +1,170 @@
+use crate::{ filter_allowed, find_preferred_with, Distance, SelectionError, SelectionOptions, Value };
+
+/// Measured network throughput constraining a selection to values
+/// whose bitrate it can carry.
+///
+/// # Examples
+///
+/// ```
+/// # use task_rust::{ SelectionOptions, Throughput, ThroughputSelection, Value::* };
+/// let available = [ ( 360, 800_000 ), ( 720, 2_500_000 ), ( 1080, 5_000_000 ) ];
+/// let throughput = Throughput { measured : 4_000_000, safety : 0.8 };
+/// assert_eq!( throughput.budget(), 3_200_000 );
+/// assert_eq!
+/// (
+///   throughput.attempt( &available, &[ Any ], &[ Number( 1080 ) ], &SelectionOptions::default() ),
+///   Ok( ThroughputSelection { selected : vec![ 720 ], fits : true } ),
+/// );
+/// ```
+#[ derive( Debug, PartialEq, Clone, Copy ) ]
+pub struct Throughput
+{
+  /// Measured throughput in bit/s.
+  pub measured : u64,
+  /// Share of `measured` which may be used, usually below 1
+  /// to leave room for throughput drops.
+  pub safety : f64,
+}
+
+/// Result of [`Throughput::attempt`].
+#[ derive( Debug, PartialEq, Eq, Clone ) ]
+pub struct ThroughputSelection< T = i32 >
+{
+  pub selected : Vec< T >,
+  /// `false` if no allowed value fits, so the lowest
+  /// one, if any, was selected instead.
+  pub fits : bool,
+}
+
+impl Throughput
+{
+  /// Returns bitrate in bit/s which may be used,
+  /// `measured` scaled by `safety`.
+  pub fn budget( &self ) -> u64
+  {
+    ( self.measured as f64 * self.safety ) as u64
+  }
+
+  /// Selects like [`attempt_with`](crate::attempt_with) out of the
+  /// values of `available`, given in any order together with their
+  /// bitrate in bit/s, whose bitrate fits in the
+  /// [`Throughput::budget`]. Since higher values usually need more
+  /// bitrate, a preferred value which does not fit falls back to a
+  /// lower one, as far as the strategy allows. If no allowed value
+  /// fits, the lowest one is selected so playback can go on, and
+  /// nothing if no value is allowed at all.
+  ///
+  /// A value listed more than once, e.g. for several codecs,
+  /// fits if any of its bitrates does.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SelectionError`] if `preferred` contains
+  /// constraints.
+  pub fn attempt< T : Ord + Clone + Distance >
+  (
+    &self,
+    available : &[ ( T, u64 ) ],
+    allowed : &[ Value< T > ],
+    preferred : &[ Value< T > ],
+    options : &SelectionOptions,
+  ) -> Result< ThroughputSelection< T >, SelectionError >
+  {
+    let budget = self.budget();
+    let fits = | value : &T | available.iter().any( | ( x, bitrate ) | x == value && *bitrate <= budget );
+
+    let mut values : Vec< _ > = available.iter().map( | ( x, _ ) | x.clone() ).collect();
+    values.sort();
+    values.dedup();
+    let allowed = filter_allowed( values, allowed.to_vec() )?;
+    let fitting : Vec< _ > = allowed.iter().filter( | x | fits( x ) ).cloned().collect();
+
+    if fitting.is_empty()
+    {
+      return Ok( ThroughputSelection { selected : allowed.into_iter().take( 1 ).collect(), fits : false } );
+    }
+    let selected = find_preferred_with( fitting, preferred.to_vec(), options.strategy )?;
+    Ok( ThroughputSelection { selected : options.order.apply( selected ), fits : true } )
+  }
+}
+
+#[ cfg( test ) ]
+mod tests
+{
+  use super::{ Throughput, ThroughputSelection };
+  use crate::{ Argument, MatchStrategy, ResultOrder, SelectionError, SelectionOptions, Tie, Value::* };
+
+  const LADDER : [ ( u32, u64 ); 4 ] = [ ( 240, 400_000 ), ( 360, 800_000 ), ( 720, 2_500_000 ), ( 1080, 5_000_000 ) ];
+
+  fn selection( selected : Vec< u32 >, fits : bool ) -> ThroughputSelection< u32 >
+  {
+    ThroughputSelection { selected, fits }
+  }
+
+  #[ test ]
+  fn test_falls_back_downward()
+  {
+    let options = SelectionOptions::default();
+    let throughput = Throughput { measured : 2_000_000, safety : 1.0 };
+    assert_eq!( throughput.attempt( &LADDER, &[ Any ], &[ Number( 1080 ) ], &options ), Ok( selection( vec![ 360 ], true ) ) );
+    assert_eq!( throughput.attempt( &LADDER, &[ Not( 360 ) ], &[ Number( 720 ) ], &options ), Ok( selection( vec![ 240 ], true ) ) );
+    assert_eq!( throughput.attempt( &LADDER, &[ Any ], &[ Any ], &options ), Ok( selection( vec![ 240, 360 ], true ) ) );
+
+    let nearest = SelectionOptions { strategy : MatchStrategy::Nearest( Tie::Up ), ..Default::default() };
+    assert_eq!( throughput.attempt( &LADDER, &[ Any ], &[ Number( 1000 ) ], &nearest ), Ok( selection( vec![ 360 ], true ) ) );
+  }
+
+  #[ test ]
+  fn test_exact()
+  {
+    let exact = SelectionOptions { strategy : MatchStrategy::ExactOnly, ..Default::default() };
+    let throughput = Throughput { measured : 2_000_000, safety : 1.0 };
+    assert_eq!( throughput.attempt( &LADDER, &[ Any ], &[ Number( 720 ) ], &exact ), Ok( selection( vec![], true ) ) );
+    assert_eq!( throughput.attempt( &LADDER, &[ Any ], &[ Number( 360 ) ], &exact ), Ok( selection( vec![ 360 ], true ) ) );
+  }
+
+  #[ test ]
+  fn test_nothing_fits()
+  {
+    let options = SelectionOptions { order : ResultOrder::Descending, ..Default::default() };
+    let throughput = Throughput { measured : 1_000_000, safety : 0.5 };
+    assert_eq!
+    (
+      throughput.attempt( &LADDER, &[ AtLeast( 360 ) ], &[ Number( 1080 ), Number( 240 ) ], &options ),
+      Ok( selection( vec![ 360 ], false ) ),
+    );
+    assert_eq!
+    (
+      throughput.attempt( &LADDER, &[ Any ], &[ Number( 1080 ), Number( 240 ) ], &options ),
+      Ok( selection( vec![ 240 ], true ) ),
+    );
+  }
+
+  #[ test ]
+  fn test_nothing_allowed()
+  {
+    let throughput = Throughput { measured : 10_000_000, safety : 1.0 };
+    assert_eq!
+    (
+      throughput.attempt( &LADDER, &[ Number( 480 ) ], &[ Any ], &SelectionOptions::default() ),
+      Ok( selection( vec![], false ) ),
+    );
+  }
+
+  #[ test ]
+  fn test_unsorted_duplicates()
+  {
+    let available = [ ( 1080, 8_000_000 ), ( 720, 2_500_000 ), ( 1080, 4_000_000 ) ];
+    let throughput = Throughput { measured : 5_000_000, safety : 0.9 };
+    assert_eq!
+    (
+      throughput.attempt( &available, &[ Any ], &[ Number( 1080 ) ], &SelectionOptions::default() ),
+      Ok( selection( vec![ 1080 ], true ) ),
+    );
+    assert_eq!
+    (
+      throughput.attempt( &available, &[ Any ], &[ AtLeast( 720 ) ], &SelectionOptions::default() ),
+      Err( SelectionError::UnexpectedConstraint { argument : Argument::Preferred } ),
+    );
+  }
+}
@@ -0,0 +1,167 @@
+use std::ops::{ Bound, RangeBounds };
+
+#[ derive( PartialEq, Eq, Clone ) ]
+/// Represents value which is either `any`, some number
+/// or a constraint on numbers
+pub enum Value< T = i32 >
+{
+  Number( T ),
+  Any,
+  /// Every number between `min` and `max`. Constraints are only
+  /// meaningful in `allowed`, see [`filter_allowed`](crate::filter_allowed).
+  Range
+  {
+    min : Bound< T >,
+    max : Bound< T >,
+  },
+  /// Every number equal or greater than the given one.
+  AtLeast( T ),
+  /// Every number equal or smaller than the given one.
+  AtMost( T ),
+  /// Excludes the given number, taking precedence over
+  /// every other value including `Any`.
+  Not( T ),
+}
+
+impl< T : std::fmt::Debug > std::fmt::Debug for Value< T >
+{
+  fn fmt( &self, f : &mut std::fmt::Formatter< '_ > ) -> std::fmt::Result
+  {
+    match self
+    {
+      Value::Number( n ) => write!( f, "{:?}", n ),
+      Value::Any => write!( f, "`any`" ),
+      Value::Range { min, max } => fmt_range( f, min, max, | f, n | write!( f, "{:?}", n ) ),
+      Value::AtLeast( n ) => write!( f, ">={:?}", n ),
+      Value::AtMost( n ) => write!( f, "<={:?}", n ),
+      Value::Not( n ) => write!( f, "!{:?}", n ),
+    }
+  }
+}
+
+/// Writes range in Rust syntax (`a..b`, `a..=b`, `a..`), writing
+/// its bounds with `number`. Excluded minimum is written as `a<..`.
+pub( crate ) fn fmt_range< T >
+(
+  f : &mut std::fmt::Formatter< '_ >,
+  min : &Bound< T >,
+  max : &Bound< T >,
+  number : impl Fn( &mut std::fmt::Formatter< '_ >, &T ) -> std::fmt::Result,
+) -> std::fmt::Result
+{
+  match min
+  {
+    Bound::Included( n ) => number( f, n )?,
+    Bound::Excluded( n ) =>
+    {
+      number( f, n )?;
+      write!( f, "<" )?;
+    }
+    Bound::Unbounded => {}
+  }
+  write!( f, ".." )?;
+  match max
+  {
+    Bound::Included( n ) =>
+    {
+      write!( f, "=" )?;
+      number( f, n )
+    }
+    Bound::Excluded( n ) => number( f, n ),
+    Bound::Unbounded => Ok( () ),
+  }
+}
+
+impl< T > Value< T >
+{
+  /// Converts numbers of the value with `f`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use task_rust::Value;
+  /// assert_eq!( Value::range( 360 .. 720 ).map( | x | x * 2 ), Value::range( 720 .. 1440 ) );
+  /// ```
+  pub fn map< U >( self, f : impl Fn( T ) -> U ) -> Value< U >
+  {
+    match self
+    {
+      Value::Number( n ) => Value::Number( f( n ) ),
+      Value::Any => Value::Any,
+      Value::Range { min, max } => Value::Range { min : min.map( &f ), max : max.map( &f ) },
+      Value::AtLeast( n ) => Value::AtLeast( f( n ) ),
+      Value::AtMost( n ) => Value::AtMost( f( n ) ),
+      Value::Not( n ) => Value::Not( f( n ) ),
+    }
+  }
+}
+
+impl< T : Clone > Value< T >
+{
+  /// Returns number contained in `Number`, or `None` if
+  /// self value is not a number.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use task_rust::Value;
+  /// assert_eq!( Value::Number( 1 ).as_number(), Some( 1 ) );
+  /// assert_eq!( Value::< i32 >::Any.as_number(), None );
+  /// ```
+  pub fn as_number( &self ) -> Option< T >
+  {
+    match self
+    {
+      Value::Number( n ) => Some( n.clone() ),
+      _ => None,
+    }
+  }
+
+  /// Creates [`Value::Range`] from a Rust range.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use std::ops::Bound;
+  /// # use task_rust::Value;
+  /// assert_eq!
+  /// (
+  ///   Value::range( 360 ..= 1080 ),
+  ///   Value::Range { min : Bound::Included( 360 ), max : Bound::Included( 1080 ) },
+  /// );
+  /// ```
+  pub fn range( range : impl RangeBounds< T > ) -> Self
+  {
+    Value::Range { min : range.start_bound().cloned(), max : range.end_bound().cloned() }
+  }
+}
+
+impl< T : Ord > Value< T >
+{
+  /// Returns `true` if `n` satisfies the value: equals the number,
+  /// lies in the range, satisfies the comparison or if self value
+  /// equals `Any`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use task_rust::Value;
+  /// assert!( Value::range( 360 .. 1080 ).matches( &720 ) );
+  /// assert!( !Value::range( 360 .. 1080 ).matches( &1080 ) );
+  /// assert!( Value::AtMost( 1080 ).matches( &1080 ) );
+  /// assert!( !Value::Not( 480 ).matches( &480 ) );
+  /// assert!( Value::Any.matches( &1080 ) );
+  /// ```
+  pub fn matches( &self, n : &T ) -> bool
+  {
+    match self
+    {
+      Value::Number( x ) => x == n,
+      Value::Any => true,
+      Value::Range { min, max } => ( min.as_ref(), max.as_ref() ).contains( n ),
+      Value::AtLeast( x ) => n >= x,
+      Value::AtMost( x ) => n <= x,
+      Value::Not( x ) => x != n,
+    }
+  }
+}